@@ -24,12 +24,32 @@ impl HttpError {
             url: None,
         }
     }
+    /// The request or response timed out.
+    pub fn is_timeout(&self) -> bool {
+        matches!(self.kind, Kind::TimedOut)
+    }
+    /// No connection to the server could be established.
+    pub fn is_connect(&self) -> bool {
+        matches!(self.kind, Kind::Connect)
+    }
+    /// Sending the request or receiving the response failed.
+    pub fn is_request(&self) -> bool {
+        matches!(self.kind, Kind::Request | Kind::Dispatch)
+    }
+    /// The status code, for errors caused by one.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self.kind {
+            Kind::Status(code) => Some(code),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug)]
 pub(crate) enum Kind {
     Request,
     Status(StatusCode),
+    Connect,
     TimedOut,
     Dispatch,
 }
@@ -43,12 +63,18 @@ impl From<reqwest::Error> for HttpError {
             )
         } else if e.is_timeout() {
             Kind::TimedOut
+        } else if e.is_connect() {
+            Kind::Connect
         } else if e.is_request() {
             Kind::Request
         } else {
             Kind::Dispatch
         };
-        Self::new(kind, e)
+        let url = e.url().cloned();
+        Self {
+            url,
+            ..Self::new(kind, e)
+        }
     }
 }
 
@@ -93,13 +119,17 @@ impl fmt::Display for HttpError {
             Kind::Status(ref code) => {
                 let prefix = if code.is_client_error() {
                     "HTTP status client error"
-                } else {
-                    debug_assert!(code.is_server_error());
+                } else if code.is_server_error() {
                     "HTTP status server error"
+                } else {
+                    "HTTP status"
                 };
                 write!(f, "{} ({})", prefix, code)?;
             }
-            _ => unimplemented!(),
+            Kind::Request => f.write_str("error building request")?,
+            Kind::Connect => f.write_str("error connecting to server")?,
+            Kind::TimedOut => f.write_str("operation timed out")?,
+            Kind::Dispatch => f.write_str("error sending request or receiving response")?,
         };
 
         ForUrl(self.url.as_ref()).fmt(f)?;
@@ -1,30 +1,133 @@
-use http_client::HttpClient;
-use hyper::{header::HeaderName, Method};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
 
-use crate::{oss::OSSClient, GetObjectOptions};
+use chrono::Utc;
+use filetime::FileTime;
+use futures::StreamExt;
+use http_client::{HttpClient, Params};
+use hyper::{
+    header::{
+        HeaderName, HeaderValue, ACCESS_CONTROL_REQUEST_HEADERS, ACCESS_CONTROL_REQUEST_METHOD,
+        CONTENT_ENCODING, CONTENT_RANGE, ORIGIN,
+    },
+    Method, StatusCode,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use tokio::{fs, io::AsyncWriteExt};
+
+use crate::multipart::{PartReader, DEFAULT_PART_SIZE, MIN_PART_SIZE};
+use crate::Preconditions;
+use crate::{oss::OSSClient, ByteStream, Crc64, GetObjectOptions, IntegrityError};
+use crate::{types::OSS_OBJECT_ACL, OSS_COPY_SOURCE, OSS_RANGE_BEHAVIOR, OSS_TRAFFIC_LIMIT};
+use crate::{Acl, Progress, RateLimiter};
+use crate::{ByteRange, Conditional, ContentRange, CopyObjectOptions, CorsPreflight, Error};
 use crate::{HeadObjectOptions, Payload, PutObjectOptions, Request, Response, Result};
 
+/// Object names in x-oss-copy-source are URL-encoded, keeping the path separators.
+const COPY_SOURCE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'/')
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
 impl<C: HttpClient> OSSClient<C> {
     pub async fn get_object<S, Opts>(&self, object: S, options: Opts) -> Result<Response>
     where
         S: AsRef<str>,
         Opts: Into<Option<GetObjectOptions>>,
     {
+        self.do_get_object(object.as_ref(), options.into().unwrap_or_default(), false)
+            .await
+    }
+
+    /// Read `range` of `object`, returning the parsed `Content-Range` along with the body.
+    ///
+    /// The range is sent with `x-oss-range-behavior: standard`, so OSS rejects a range past the
+    /// end of the object instead of returning all of it. A response without a matching
+    /// `Content-Range` is an error rather than silently being the whole object.
+    pub async fn get_object_range<S, R, Opts>(
+        &self,
+        object: S,
+        range: R,
+        options: Opts,
+    ) -> Result<(ContentRange, Response)>
+    where
+        S: AsRef<str>,
+        R: Into<ByteRange>,
+        Opts: Into<Option<GetObjectOptions>>,
+    {
+        let range = range.into();
+        let mut opts = options.into().unwrap_or_default();
+        opts.range = Some(range.to_header()?);
+        let ret = self.do_get_object(object.as_ref(), opts, true).await?;
+        if ret.status != StatusCode::PARTIAL_CONTENT {
+            return Err(Error::range(format!(
+                "expected 206 Partial Content, got {}",
+                ret.status
+            )));
+        }
+        let content_range = ret
+            .headers
+            .get(CONTENT_RANGE)
+            .ok_or_else(|| Error::range("missing Content-Range"))?
+            .to_str()
+            .map_err(Error::header_to_str_error)?
+            .parse()?;
+        if !range.is_satisfied_by(&content_range) {
+            return Err(Error::range(format!(
+                "requested {:?}, got {}",
+                range, content_range
+            )));
+        }
+        Ok((content_range, ret))
+    }
+
+    async fn do_get_object(
+        &self,
+        object: &str,
+        opts: GetObjectOptions,
+        standard_range: bool,
+    ) -> Result<Response> {
         let mut rqst = Request::new(
             Method::GET,
             self.get_bucket(),
-            Some(object.as_ref()),
+            Some(object),
             self.get_schema(),
             None,
             None,
             None,
         );
-        let opts = options.into().unwrap_or_default();
         for (key, val) in opts.to_opts() {
             rqst.headers_mut()
                 .insert(key.parse::<HeaderName>()?, val.parse()?);
         }
-        self.sign_and_dispatch(rqst).await
+        rqst.add_preconditions(opts.preconditions.as_ref())?;
+        if standard_range {
+            rqst.headers_mut()
+                .insert(OSS_RANGE_BEHAVIOR, HeaderValue::from_static("standard"));
+        }
+        if let Some(limit) = opts.traffic_limit {
+            rqst.headers_mut()
+                .insert(OSS_TRAFFIC_LIMIT, limit.bits_per_second().into());
+        }
+        rqst.set_progress(opts.progress.clone());
+        let mut ret = self.sign_and_dispatch(rqst).await?;
+        if opts.check_crc64.unwrap_or(true) {
+            if let Some(expected) = download_crc64(&ret) {
+                ret.body = ret.body.verify_crc64(expected);
+            }
+        }
+        if let Some(limiter) = opts.rate_limiter {
+            ret.body = ret.body.throttle(limiter);
+        }
+        if let Some(progress) = opts.progress {
+            let total = ret.body.size_hint().1.map(|len| len as u64);
+            ret.body = ret.body.with_progress(progress, total);
+        }
+        Ok(ret)
     }
 
     pub async fn head_object<S, Opts>(&self, object: S, options: Opts) -> Result<Response>
@@ -46,9 +149,84 @@ impl<C: HttpClient> OSSClient<C> {
             rqst.headers_mut()
                 .insert(key.parse::<HeaderName>()?, val.parse()?);
         }
+        rqst.add_preconditions(opts.preconditions.as_ref())?;
         self.sign_and_dispatch(rqst).await
     }
 
+    /// `get_object` under `preconditions`, with 304 and 412 as distinct outcomes.
+    pub async fn get_object_conditional<S, Opts>(
+        &self,
+        object: S,
+        preconditions: Preconditions,
+        options: Opts,
+    ) -> Result<Conditional<Response>>
+    where
+        S: AsRef<str>,
+        Opts: Into<Option<GetObjectOptions>>,
+    {
+        let mut opts = options.into().unwrap_or_default();
+        opts.preconditions = Some(preconditions);
+        Conditional::from_result(self.get_object(object, opts).await)
+    }
+
+    /// `head_object` under `preconditions`, with 304 and 412 as distinct outcomes.
+    pub async fn head_object_conditional<S, Opts>(
+        &self,
+        object: S,
+        preconditions: Preconditions,
+        options: Opts,
+    ) -> Result<Conditional<Response>>
+    where
+        S: AsRef<str>,
+        Opts: Into<Option<HeadObjectOptions>>,
+    {
+        let mut opts = options.into().unwrap_or_default();
+        opts.preconditions = Some(preconditions);
+        Conditional::from_result(self.head_object(object, opts).await)
+    }
+
+    /// Send the CORS preflight a browser would before a `method` request on `object` from
+    /// `origin`, with Access-Control-Request-Headers `request_headers`.
+    ///
+    /// A preflight the CORS rules of the bucket do not allow fails with 403 Forbidden.
+    pub async fn options_object<S>(
+        &self,
+        object: S,
+        origin: &str,
+        method: Method,
+        request_headers: &[&str],
+    ) -> Result<CorsPreflight>
+    where
+        S: AsRef<str>,
+    {
+        let mut rqst = Request::new(
+            Method::OPTIONS,
+            self.get_bucket(),
+            Some(object.as_ref()),
+            self.get_schema(),
+            None,
+            None,
+            None,
+        );
+        let headers = rqst.headers_mut();
+        headers.insert(ORIGIN, origin.parse()?);
+        headers.insert(ACCESS_CONTROL_REQUEST_METHOD, method.as_str().parse()?);
+        if !request_headers.is_empty() {
+            headers.insert(
+                ACCESS_CONTROL_REQUEST_HEADERS,
+                request_headers.join(",").parse()?,
+            );
+        }
+        let ret = self.sign_and_dispatch(rqst).await?;
+        Ok(CorsPreflight::from_headers(&ret.headers))
+    }
+
+    /// Upload `payload` as `object`.
+    ///
+    /// A payload of unknown length, such as a `ByteStream` without size hint, is read in parts of
+    /// `options.part_size`. It is sent as a multipart upload when it does not fit in one part.
+    /// A multipart upload can't take `options.content_md5`, and gets `options.object_acl` set
+    /// once it is complete.
     pub async fn put_object<S, Opts>(
         &self,
         object: S,
@@ -59,6 +237,35 @@ impl<C: HttpClient> OSSClient<C> {
         S: AsRef<str>,
         Opts: Into<Option<PutObjectOptions>>,
     {
+        let mut opts = options.into().unwrap_or_default();
+        if matches!(opts.part_size, Some(part_size) if part_size < MIN_PART_SIZE) {
+            return Err(Error::invalid_argument(format!(
+                "part_size is at least {} bytes",
+                MIN_PART_SIZE
+            )));
+        }
+        let payload = match payload.len() {
+            Some(_) => payload,
+            None => {
+                let part_size = opts.part_size.unwrap_or(DEFAULT_PART_SIZE);
+                let mut reader = PartReader::new(payload.into_stream(), part_size as usize);
+                let first = reader.next_part().await?.unwrap_or_default();
+                if !reader.at_end().await? {
+                    return self
+                        .put_object_in_parts(object.as_ref(), first, reader, opts)
+                        .await;
+                }
+                Payload::Buffer(first)
+            }
+        };
+        let payload =
+            with_content_md5(payload, &mut opts.content_md5, opts.compute_content_md5).await?;
+        let (payload, crc) = wrap_upload(
+            payload,
+            opts.check_crc64.unwrap_or(true),
+            opts.rate_limiter.clone(),
+            opts.progress.clone(),
+        );
         let mut rqst = Request::new(
             Method::PUT,
             self.get_bucket(),
@@ -68,20 +275,251 @@ impl<C: HttpClient> OSSClient<C> {
             None,
             None,
         );
+        rqst.add_metas(opts.metas.as_ref())?;
+        rqst.add_server_side_encryption(opts.server_side_encryption.as_ref())?;
+        rqst.add_acl(OSS_OBJECT_ACL, opts.object_acl);
+        for (key, val) in opts.to_opts() {
+            rqst.headers_mut()
+                .insert(key.parse::<HeaderName>()?, val.parse()?);
+        }
+        if let Some(limit) = opts.traffic_limit {
+            rqst.headers_mut()
+                .insert(OSS_TRAFFIC_LIMIT, limit.bits_per_second().into());
+        }
+        rqst.set_progress(opts.progress.clone());
+        let ret = self.sign_and_dispatch(rqst).await?;
+        verify_upload(&ret, crc.as_ref())?;
+        Ok(ret)
+    }
+
+    /// Copy `source` to `object`, within the bucket of the client unless
+    /// `options.source_bucket` names another one.
+    pub async fn copy_object<S1, S2, Opts>(
+        &self,
+        source: S1,
+        object: S2,
+        options: Opts,
+    ) -> Result<Response>
+    where
+        S1: AsRef<str>,
+        S2: AsRef<str>,
+        Opts: Into<Option<CopyObjectOptions>>,
+    {
         let opts = options.into().unwrap_or_default();
+        let source_bucket = opts
+            .source_bucket
+            .as_deref()
+            .or_else(|| self.get_bucket())
+            .ok_or_else(|| {
+                Error::invalid_argument("the client has no bucket and no source_bucket is given")
+            })?;
+        let copy_source = format!(
+            "/{}/{}",
+            source_bucket,
+            utf8_percent_encode(source.as_ref(), COPY_SOURCE_ENCODE_SET)
+        );
+        let mut rqst = Request::new(
+            Method::PUT,
+            self.get_bucket(),
+            Some(object.as_ref()),
+            self.get_schema(),
+            None,
+            None,
+            None,
+        );
+        rqst.headers_mut()
+            .insert(OSS_COPY_SOURCE, copy_source.parse()?);
         rqst.add_metas(opts.metas.as_ref())?;
+        rqst.add_server_side_encryption(opts.server_side_encryption.as_ref())?;
+        rqst.add_copy_source_preconditions(opts.copy_source_preconditions.as_ref())?;
+        rqst.add_acl(OSS_OBJECT_ACL, opts.object_acl);
         for (key, val) in opts.to_opts() {
             rqst.headers_mut()
                 .insert(key.parse::<HeaderName>()?, val.parse()?);
         }
         self.sign_and_dispatch(rqst).await
     }
+
+    /// Set the canned ACL of `object`, `Acl::Default` for that of the bucket.
+    pub async fn put_object_acl<S>(&self, object: S, acl: Acl) -> Result<Response>
+    where
+        S: AsRef<str>,
+    {
+        let mut params = Params::new();
+        params.insert("acl".to_owned(), None);
+        let mut rqst = Request::new(
+            Method::PUT,
+            self.get_bucket(),
+            Some(object.as_ref()),
+            self.get_schema(),
+            None,
+            Some(params),
+            None,
+        );
+        rqst.add_acl(OSS_OBJECT_ACL, Some(acl));
+        self.sign_and_dispatch(rqst).await
+    }
+
+    /// Upload the file at `path` as `object`.
+    ///
+    /// Content-Length is taken from the file size, and Content-Type is guessed from the
+    /// file extension unless `options` sets it.
+    pub async fn put_object_from_file<S, P, Opts>(
+        &self,
+        object: S,
+        path: P,
+        options: Opts,
+    ) -> Result<Response>
+    where
+        S: AsRef<str>,
+        P: AsRef<Path>,
+        Opts: Into<Option<PutObjectOptions>>,
+    {
+        let path = path.as_ref();
+        let payload = Payload::from_path(path).await?;
+        let mut opts = options.into().unwrap_or_default();
+        if opts.content_type.is_none() {
+            opts.content_type = mime_guess::from_path(path)
+                .first()
+                .map(|mime| mime.to_string());
+        }
+        self.put_object(object, payload, opts).await
+    }
+
+    /// Download `object` into the file at `path`.
+    ///
+    /// The body is written to a temporary file next to `path`, synced and then renamed over
+    /// `path`, so readers never observe a partial download. The file's mtime is set from
+    /// Last-Modified. The returned `Response` carries the status and headers only, its body
+    /// has already been consumed.
+    pub async fn get_object_to_file<S, P, Opts>(
+        &self,
+        object: S,
+        path: P,
+        options: Opts,
+    ) -> Result<Response>
+    where
+        S: AsRef<str>,
+        P: AsRef<Path>,
+        Opts: Into<Option<GetObjectOptions>>,
+    {
+        let path = path.as_ref();
+        let mut ret = self.get_object(object, options).await?;
+        let body = std::mem::replace(&mut ret.body, ByteStream::from(Vec::new()));
+        let tmp_path = temp_path(path);
+        if let Err(e) = write_atomically(body, &tmp_path, path).await {
+            let _ = fs::remove_file(&tmp_path).await;
+            return Err(e);
+        }
+        if let Some(last_modified) = ret.metadata().last_modified {
+            let mtime = FileTime::from_unix_time(last_modified.timestamp(), 0);
+            filetime::set_file_mtime(path, mtime)?;
+        }
+        Ok(ret)
+    }
+}
+
+/// Fill in `content_md5` from the payload when asked to and it is not given yet.
+pub(crate) async fn with_content_md5(
+    payload: Payload,
+    content_md5: &mut Option<String>,
+    compute: bool,
+) -> Result<Payload> {
+    if !compute || content_md5.is_some() {
+        return Ok(payload);
+    }
+    let payload = payload.buffered().await?;
+    *content_md5 = payload.content_md5();
+    Ok(payload)
+}
+
+/// Wrap an upload payload for CRC64 hashing, throttling and progress reporting.
+///
+/// The returned hasher is `Some` when `check_crc64` is set, see `verify_upload`.
+pub(crate) fn wrap_upload(
+    payload: Payload,
+    check_crc64: bool,
+    rate_limiter: Option<RateLimiter>,
+    progress: Option<Progress>,
+) -> (Payload, Option<Arc<Mutex<Crc64>>>) {
+    let (payload, crc) = if check_crc64 {
+        let crc = Arc::new(Mutex::new(Crc64::new()));
+        (payload.hash_crc64(&crc), Some(crc))
+    } else {
+        (payload, None)
+    };
+    let payload = match rate_limiter {
+        Some(limiter) => payload.throttle(limiter),
+        None => payload,
+    };
+    let payload = match progress {
+        Some(progress) => payload.with_progress(progress),
+        None => payload,
+    };
+    (payload, crc)
+}
+
+/// The CRC64-ECMA the body of a GET response can be checked against.
+///
+/// The reported checksum covers the whole object as stored, so partial content and bodies
+/// compressed on the way, which the client does not decode, can't be checked.
+fn download_crc64(ret: &Response) -> Option<u64> {
+    let encoded = matches!(
+        ret.headers.get(CONTENT_ENCODING),
+        Some(encoding) if encoding != "identity"
+    );
+    if ret.status != StatusCode::OK || encoded {
+        return None;
+    }
+    ret.crc64()
+}
+
+/// Compare the CRC64 computed while uploading with the one OSS reports.
+pub(crate) fn verify_upload(ret: &Response, crc: Option<&Arc<Mutex<Crc64>>>) -> Result<()> {
+    if let (Some(crc), Some(expected)) = (crc, ret.crc64()) {
+        let actual = crc.lock().unwrap().value();
+        if actual != expected {
+            return Err(IntegrityError { expected, actual }.into());
+        }
+    }
+    Ok(())
+}
+
+/// A hidden sibling of `path`, unique to this process and moment.
+fn temp_path(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(
+        ".{}.{}.{}.tmp",
+        file_name,
+        std::process::id(),
+        Utc::now().timestamp_subsec_nanos()
+    ))
+}
+
+async fn write_atomically(mut body: ByteStream, tmp_path: &Path, path: &Path) -> Result<()> {
+    let mut file = fs::File::create(tmp_path).await?;
+    while let Some(chunk) = body.next().await {
+        file.write_all(&chunk?).await?;
+    }
+    file.sync_all().await?;
+    drop(file);
+    fs::rename(tmp_path, path).await?;
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::Metas;
+    use crate::types::{ETag, Metas, ServerSideEncryption};
+    use crate::{ErrorKind, ProgressEvent, RetryPolicy, UploadPartOptions, OSS_HASH_CRC64};
+    use async_trait::async_trait;
+    use http_client::{HttpError, HttpRequest, HttpResponse};
+    use hyper::{header::ETAG, http::request::Parts, HeaderMap};
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::time::Duration;
     use tokio::io::AsyncReadExt;
     const BUF: &[u8] = "This is just a put test".as_bytes();
     const FILE_NAME: &str = "test-with-header";
@@ -89,6 +527,52 @@ mod tests {
     const META_KEY_WITH_PREFIX: &str = "x-oss-meta-test-meta-key";
     const META_VAL: &str = "test-meta-val";
 
+    #[test]
+    fn download_crc64_test() {
+        let response = |status, encoding: Option<&'static str>| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                OSS_HASH_CRC64,
+                HeaderValue::from_static("5981764153023615706"),
+            );
+            if let Some(encoding) = encoding {
+                headers.insert(CONTENT_ENCODING, HeaderValue::from_static(encoding));
+            }
+            Response {
+                status,
+                body: ByteStream::from(BUF.to_vec()),
+                headers,
+            }
+        };
+        assert_eq!(
+            download_crc64(&response(StatusCode::OK, None)),
+            Some(5981764153023615706)
+        );
+        assert_eq!(
+            download_crc64(&response(StatusCode::OK, Some("identity"))),
+            Some(5981764153023615706)
+        );
+        assert_eq!(
+            download_crc64(&response(StatusCode::OK, Some("gzip"))),
+            None
+        );
+        assert_eq!(
+            download_crc64(&response(StatusCode::PARTIAL_CONTENT, None)),
+            None
+        );
+    }
+    #[tokio::test]
+    async fn copy_object_without_bucket_test() {
+        let oss_cli = OSSClient::new_with_default_client(
+            "北京",
+            None,
+            None,
+            "key-id".to_owned(),
+            "key-secret".to_owned(),
+        );
+        let ret = oss_cli.copy_object("source", "object", None).await;
+        assert!(matches!(ret, Err(err) if err.kind() == ErrorKind::InvalidArgument));
+    }
     #[tokio::test]
     async fn get_object_test() {
         let oss_cli = oss_client();
@@ -120,6 +604,7 @@ mod tests {
         metas.insert(META_KEY.to_owned(), META_VAL.to_owned());
         let opts = PutObjectOptions {
             metas: Some(metas),
+            compute_content_md5: true,
             ..Default::default()
         };
         let payload = Payload::Buffer(BUF.into());
@@ -163,11 +648,251 @@ mod tests {
         println!("Body: {}", buf);
     }
     #[tokio::test]
+    async fn file_object_test() {
+        let oss_cli = oss_client();
+        let dir = std::env::temp_dir();
+        let src = dir.join("oss_sdk_put_from_file.txt");
+        let dst = dir.join("oss_sdk_get_to_file.txt");
+        fs::write(&src, BUF).await.unwrap();
+
+        let ret = oss_cli
+            .put_object_from_file("test-from-file.txt", &src, None)
+            .await
+            .unwrap();
+        assert!(ret.status.is_success());
+
+        let ret = oss_cli
+            .get_object_to_file("test-from-file.txt", &dst, None)
+            .await
+            .unwrap();
+        println!("headers: {:?}", ret.headers);
+        assert_eq!(fs::read(&dst).await.unwrap(), BUF);
+    }
+    #[tokio::test]
+    async fn copy_object_test() {
+        let oss_cli = oss_client();
+        let opts = CopyObjectOptions {
+            server_side_encryption: Some(ServerSideEncryption::Aes256),
+            ..Default::default()
+        };
+        let ret = oss_cli
+            .copy_object(FILE_NAME, "test-copy", opts)
+            .await
+            .unwrap();
+        println!("StatusCode: {}", ret.status);
+        assert_eq!(
+            ret.server_side_encryption(),
+            Some(ServerSideEncryption::Aes256)
+        );
+    }
+    #[tokio::test]
+    async fn get_object_range_test() {
+        let oss_cli = oss_client();
+        let (range, ret) = oss_cli
+            .get_object_range(FILE_NAME, 0..4, None)
+            .await
+            .unwrap();
+        println!("Content-Range: {}", range);
+        assert_eq!((range.start, range.end), (0, 3));
+        assert_eq!(ret.into_bytes().await.unwrap().len(), 4);
+        let ret = oss_cli
+            .get_object_range(FILE_NAME, u64::MAX - 1.., None)
+            .await;
+        assert!(ret.is_err());
+    }
+    #[tokio::test]
+    async fn conditional_test() {
+        let oss_cli = oss_client();
+        let ret = oss_cli.head_object(FILE_NAME, None).await.unwrap();
+        let etag = ETag::new(ret.headers["etag"].to_str().unwrap());
+        let preconditions = Preconditions {
+            if_none_match: Some(etag.clone()),
+            ..Default::default()
+        };
+        let ret = oss_cli
+            .get_object_conditional(FILE_NAME, preconditions, None)
+            .await
+            .unwrap();
+        assert!(matches!(ret, Conditional::NotModified));
+        let preconditions = Preconditions {
+            if_match: Some(etag),
+            if_unmodified_since: Some(Utc::now()),
+            ..Default::default()
+        };
+        let ret = oss_cli
+            .head_object_conditional(FILE_NAME, preconditions, None)
+            .await
+            .unwrap();
+        assert!(ret.satisfied().is_some());
+    }
+    #[tokio::test]
+    async fn put_unsized_stream_test() {
+        let oss_cli = oss_client();
+        let chunks = (0..20).map(|_| Ok(bytes::Bytes::from(vec![b'x'; 64 * 1024])));
+        let payload = Payload::Stream(ByteStream::new(futures::stream::iter(chunks)));
+        let opts = PutObjectOptions {
+            part_size: Some(512 * 1024),
+            ..Default::default()
+        };
+        let ret = oss_cli
+            .put_object("test-unsized", payload, opts)
+            .await
+            .unwrap();
+        println!("headers: {:?}", ret.headers);
+        let ret = oss_cli.head_object("test-unsized", None).await.unwrap();
+        assert_eq!(ret.metadata().content_length, Some(20 * 64 * 1024));
+    }
+    #[tokio::test]
     async fn head_object_test() {
         let oss_cli = oss_client();
         let ret = oss_cli.head_object(FILE_NAME, None).await.unwrap();
         println!("StatusCode: {}", ret.status.to_string());
         println!("headers: {:?}", ret.headers);
+        let metadata = ret.metadata();
+        assert_eq!(metadata.metas.get(META_KEY), Some(&META_VAL.to_owned()));
+        assert!(metadata.etag.is_some());
+    }
+    #[tokio::test]
+    async fn retried_upload_progress_test() {
+        let (oss_cli, _) = mock_client(|sent, _| {
+            // Every first attempt fails with a retryable server error.
+            if sent % 2 == 0 {
+                mock_response(StatusCode::SERVICE_UNAVAILABLE, "")
+            } else {
+                mock_response(StatusCode::OK, "")
+            }
+        });
+        let retry_policy =
+            RetryPolicy::new(2).with_backoff(Duration::from_millis(0), Duration::from_millis(0));
+        let oss_cli = oss_cli.with_retry_policy(retry_policy);
+        let retried = Arc::new(AtomicU32::new(0));
+        let progress = {
+            let retried = retried.clone();
+            Progress::new(move |event| {
+                if let ProgressEvent::Retried { attempt } = event {
+                    assert_eq!(attempt, 1);
+                    retried.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        };
+
+        let opts = PutObjectOptions {
+            progress: Some(progress.clone()),
+            ..Default::default()
+        };
+        let payload = Payload::Buffer(BUF.into());
+        oss_cli.put_object("object", payload, opts).await.unwrap();
+        assert_eq!(retried.load(Ordering::SeqCst), 1);
+
+        let opts = UploadPartOptions {
+            progress: Some(progress),
+            ..Default::default()
+        };
+        let payload = Payload::Buffer(BUF.into());
+        oss_cli
+            .upload_part("object", "upload-id", 1, payload, opts)
+            .await
+            .unwrap();
+        assert_eq!(retried.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn unsized_put_acl_test() {
+        let put = |chunks: usize| async move {
+            let (oss_cli, requests) = mock_client(|_, parts| {
+                if parts.uri.query() == Some("uploads") {
+                    mock_response(
+                        StatusCode::OK,
+                        "<InitiateMultipartUploadResult><Bucket>bucket</Bucket>\
+                         <Key>object</Key><UploadId>upload-id</UploadId>\
+                         </InitiateMultipartUploadResult>",
+                    )
+                } else {
+                    mock_response(StatusCode::OK, "")
+                }
+            });
+            let chunks = (0..chunks).map(|_| Ok(bytes::Bytes::from(vec![b'x'; 64 * 1024])));
+            let payload = Payload::Stream(ByteStream::new(futures::stream::iter(chunks)));
+            let opts = PutObjectOptions {
+                object_acl: Some(Acl::PublicRead),
+                part_size: Some(MIN_PART_SIZE),
+                ..Default::default()
+            };
+            oss_cli.put_object("object", payload, opts).await.unwrap();
+            let requests = std::mem::take(&mut *requests.lock().unwrap());
+            requests
+        };
+        // A payload fitting in one part is sent with its ACL.
+        let requests = put(1).await;
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].headers[OSS_OBJECT_ACL], "public-read");
+
+        // Parts are sent without, and the ACL is set on the completed object.
+        let requests = put(3).await;
+        let sent: Vec<_> = requests
+            .iter()
+            .map(|parts| (parts.method.clone(), parts.uri.query().unwrap_or_default()))
+            .collect();
+        assert_eq!(
+            sent,
+            vec![
+                (Method::POST, "uploads"),
+                (Method::PUT, "partNumber=1&uploadId=upload-id"),
+                (Method::PUT, "partNumber=2&uploadId=upload-id"),
+                (Method::POST, "uploadId=upload-id"),
+                (Method::PUT, "acl"),
+            ]
+        );
+        assert!(requests[..4]
+            .iter()
+            .all(|parts| !parts.headers.contains_key(OSS_OBJECT_ACL)));
+        assert_eq!(requests[4].headers[OSS_OBJECT_ACL], "public-read");
+    }
+
+    /// Answers each request with `respond`, given the number of requests received before.
+    struct MockClient<F> {
+        respond: F,
+        requests: Arc<Mutex<Vec<Parts>>>,
+    }
+    #[async_trait]
+    impl<F> HttpClient for MockClient<F>
+    where
+        F: Fn(usize, &Parts) -> HttpResponse + Send + Sync,
+    {
+        async fn dispatch(
+            &self,
+            request: HttpRequest,
+        ) -> std::result::Result<HttpResponse, HttpError> {
+            let (parts, body) = hyper::Request::from(request).into_parts();
+            // Read the body as a server would, so that upload progress is reported.
+            hyper::body::to_bytes(body).await.unwrap();
+            let mut requests = self.requests.lock().unwrap();
+            let response = (self.respond)(requests.len(), &parts);
+            requests.push(parts);
+            Ok(response)
+        }
+    }
+    /// A client sending to a `MockClient`, and the requests it received.
+    fn mock_client<F>(respond: F) -> (OSSClient<MockClient<F>>, Arc<Mutex<Vec<Parts>>>)
+    where
+        F: Fn(usize, &Parts) -> HttpResponse + Send + Sync,
+    {
+        let requests = Arc::new(Mutex::new(vec![]));
+        let client = MockClient {
+            respond,
+            requests: requests.clone(),
+        };
+        let oss_cli = OSSClient::new(client, "北京", None, "bucket", "key-id", "key-secret");
+        (oss_cli, requests)
+    }
+    fn mock_response(status: StatusCode, body: &'static str) -> HttpResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"etag\""));
+        HttpResponse {
+            status,
+            body: body.into(),
+            headers,
+        }
     }
     fn oss_client() -> OSSClient<http_client::DefaultClient> {
         let bucket = std::env::var("OSS_BUCKET").unwrap();
@@ -7,7 +7,11 @@ use crypto::sha1::Sha1;
 use http_client::{HttpClient, Params};
 use hyper::header::{HeaderName, HeaderValue};
 
-use crate::{statics::OSS_CANONICALIZED_PREFIX, types::*, OSSClient, RESOURCES};
+use crate::{
+    statics::{CONTENT_MD5, OSS_CANONICALIZED_PREFIX},
+    types::*,
+    OSSClient, RESOURCES,
+};
 
 impl<C: HttpClient> OSSClient<C> {
     /// Add Signatures to Headers
@@ -20,44 +24,7 @@ impl<C: HttpClient> OSSClient<C> {
         self.add_authorization_header(rqst)
     }
     fn add_authorization_header(&self, rqst: &mut Request) -> Result<()> {
-        let headers = rqst.headers();
-        let date = headers
-            .get("date")
-            .and_then(|val| val.to_str().ok())
-            .unwrap_or_default();
-        let content_type = headers
-            .get("content-type")
-            .and_then(|val| val.to_str().ok())
-            .unwrap_or_default();
-
-        let content_md5 = headers
-            .get("Content-MD5")
-            .map(|val| encode(val.to_str().ok().unwrap_or_default()))
-            .unwrap_or_default();
-
-        let mut oss_headers_str = String::new();
-        for (k, v) in headers.iter().filter(|(k, _)| {
-            k.as_str().contains(OSS_CANONICALIZED_PREFIX)
-            // && !k.as_str().contains(OSS_META_PREFIX)
-        }) {
-            oss_headers_str += &format!(
-                "{}:{}\n",
-                k,
-                v.to_str().map_err(Error::header_to_str_error)?
-            );
-        }
-
-        let oss_resource_str =
-            canonicalized_resource(self.get_bucket(), rqst.get_object(), rqst.get_params());
-        let sign_str = format!(
-            "{}\n{}\n{}\n{}\n{}{}",
-            rqst.get_method(),
-            content_md5,
-            content_type,
-            date,
-            oss_headers_str,
-            oss_resource_str
-        );
+        let sign_str = string_to_sign(rqst)?;
 
         let (access_key_id, access_key_secret) = self.get_access_key();
         let mut hasher = Hmac::new(Sha1::new(), access_key_secret.as_bytes());
@@ -71,6 +38,54 @@ impl<C: HttpClient> OSSClient<C> {
         Ok(())
     }
 }
+
+/// The string the signature of `rqst` is computed over.
+fn string_to_sign(rqst: &Request) -> Result<String> {
+    let headers = rqst.headers();
+    let date = headers
+        .get("date")
+        .and_then(|val| val.to_str().ok())
+        .unwrap_or_default();
+    let content_type = headers
+        .get("content-type")
+        .and_then(|val| val.to_str().ok())
+        .unwrap_or_default();
+
+    // Content-MD5 already carries the base64 encoded digest, it is signed verbatim.
+    let content_md5 = headers
+        .get(CONTENT_MD5)
+        .and_then(|val| val.to_str().ok())
+        .unwrap_or_default();
+
+    // CanonicalizedOSSHeaders are sorted by their lowercase names, whatever order they
+    // were added in.
+    let mut oss_headers: Vec<_> = headers
+        .iter()
+        .filter(|(k, _)| k.as_str().contains(OSS_CANONICALIZED_PREFIX))
+        .collect();
+    oss_headers.sort_by(|a, b| a.0.as_str().cmp(b.0.as_str()));
+    let mut oss_headers_str = String::new();
+    for (k, v) in oss_headers {
+        oss_headers_str += &format!(
+            "{}:{}\n",
+            k,
+            v.to_str().map_err(Error::header_to_str_error)?
+        );
+    }
+
+    let oss_resource_str =
+        canonicalized_resource(rqst.get_bucket(), rqst.get_object(), rqst.get_params());
+    let sign_str = format!(
+        "{}\n{}\n{}\n{}\n{}{}",
+        rqst.get_method(),
+        content_md5,
+        content_type,
+        date,
+        oss_headers_str,
+        oss_resource_str
+    );
+    Ok(sign_str)
+}
 /*
 1. CanonicalizedResource = "/BucketName/ObjectName", "/BucketName" or "/""  + "?" + SubResources
 2. SubResources, 将所有的子资源按照字典序，从小到大排列并以&为分隔符生成子资源字符串。
@@ -86,13 +101,11 @@ pub(crate) fn canonicalized_resource(
         ret.push('/');
         ret.push_str(bucket);
     }
+    // Bucket-level resources end with a slash, `/bucket/`.
+    ret.push('/');
     if let Some(object) = object {
-        ret.push('/');
         ret.push_str(object);
     }
-    if ret.is_empty() {
-        ret.push('/');
-    }
     ret.push_str(&get_resources_str(params));
     ret
 }
@@ -121,3 +134,84 @@ fn get_resources_str(params: &Params) -> String {
     }
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use hyper::Method;
+
+    const DATE: &str = "Wed, 19 Oct 2026 00:00:00 GMT";
+
+    fn put_request<'a>() -> Request<'a> {
+        let mut rqst = Request::new(
+            Method::PUT,
+            Some("examplebucket"),
+            Some("object"),
+            Schema::default(),
+            None,
+            None,
+            None,
+        );
+        rqst.headers_mut()
+            .insert("date", HeaderValue::from_static(DATE));
+        rqst
+    }
+
+    #[test]
+    fn kms_sm4_string_to_sign_test() {
+        let mut rqst = put_request();
+        let sse = ServerSideEncryption::Kms {
+            key_id: None,
+            data_encryption: Some(DataEncryption::Sm4),
+        };
+        rqst.add_server_side_encryption(Some(&sse)).unwrap();
+        assert_eq!(
+            string_to_sign(&rqst).unwrap(),
+            format!(
+                "PUT\n\n\n{}\nx-oss-server-side-data-encryption:SM4\n\
+                 x-oss-server-side-encryption:KMS\n/examplebucket/object",
+                DATE
+            )
+        );
+    }
+    #[test]
+    fn copy_source_preconditions_string_to_sign_test() {
+        let mut rqst = put_request();
+        rqst.headers_mut().insert(
+            "x-oss-copy-source",
+            HeaderValue::from_static("/examplebucket/source"),
+        );
+        rqst.add_server_side_encryption(Some(&ServerSideEncryption::Aes256))
+            .unwrap();
+        let preconditions = Preconditions {
+            if_match: Some(ETag::new("\"etag\"")),
+            ..Default::default()
+        };
+        rqst.add_copy_source_preconditions(Some(&preconditions))
+            .unwrap();
+        assert_eq!(
+            string_to_sign(&rqst).unwrap(),
+            format!(
+                "PUT\n\n\n{}\nx-oss-copy-source:/examplebucket/source\n\
+                 x-oss-copy-source-if-match:\"etag\"\n\
+                 x-oss-server-side-encryption:AES256\n/examplebucket/object",
+                DATE
+            )
+        );
+    }
+    #[test]
+    fn sse_object_acl_string_to_sign_test() {
+        let mut rqst = put_request();
+        rqst.add_server_side_encryption(Some(&ServerSideEncryption::kms()))
+            .unwrap();
+        rqst.add_acl(OSS_OBJECT_ACL, Some(Acl::PublicRead));
+        assert_eq!(
+            string_to_sign(&rqst).unwrap(),
+            format!(
+                "PUT\n\n\n{}\nx-oss-object-acl:public-read\n\
+                 x-oss-server-side-encryption:KMS\n/examplebucket/object",
+                DATE
+            )
+        );
+    }
+}
@@ -0,0 +1,482 @@
+use http_client::{HttpClient, Params};
+use hyper::{header::HeaderName, Method};
+
+use crate::{
+    oss::OSSClient,
+    types::{
+        create_bucket_xml, BucketInfoDocument, BucketLoggingStatus, LocationConstraint, OSS_ACL,
+    },
+    AccessControlPolicy, Acl, BucketInfo, BucketLogging, BucketStat, CorsConfiguration,
+    CreateBucketOptions, Error, LifecycleConfiguration, ListBucketsOptions, ListBucketsResult,
+    Payload, PolicyDocument, RefererConfiguration, Request, Result, WebsiteConfiguration,
+};
+
+impl<C: HttpClient> OSSClient<C> {
+    /// List the buckets of the account, a page at a time.
+    ///
+    /// The bucket of the client is ignored. While `is_truncated` is set, pass `next_marker` as
+    /// the `marker` of the next call to list the following page.
+    pub async fn list_buckets<Opts>(&self, options: Opts) -> Result<ListBucketsResult>
+    where
+        Opts: Into<Option<ListBucketsOptions>>,
+    {
+        let opts = options.into().unwrap_or_default();
+        let mut params = Params::new();
+        if let Some(prefix) = opts.prefix {
+            params.insert("prefix".to_owned(), Some(prefix));
+        }
+        if let Some(marker) = opts.marker {
+            params.insert("marker".to_owned(), Some(marker));
+        }
+        if let Some(max_keys) = opts.max_keys {
+            params.insert("max-keys".to_owned(), Some(max_keys.to_string()));
+        }
+        let rqst = Request::new(
+            Method::GET,
+            None,
+            None,
+            self.get_schema(),
+            None,
+            Some(params),
+            None,
+        );
+        self.sign_and_dispatch(rqst).await?.into_xml().await
+    }
+
+    /// Create the bucket of the client.
+    pub async fn create_bucket<Opts>(&self, options: Opts) -> Result<()>
+    where
+        Opts: Into<Option<CreateBucketOptions>>,
+    {
+        let opts = options.into().unwrap_or_default();
+        if opts.acl == Some(Acl::Default) {
+            return Err(Error::invalid_argument("a bucket ACL can't be default"));
+        }
+        let payload = if opts.storage_class.is_some() || opts.data_redundancy_type.is_some() {
+            Some(Payload::Buffer(
+                create_bucket_xml(opts.storage_class, opts.data_redundancy_type).into(),
+            ))
+        } else {
+            None
+        };
+        let mut rqst = Request::new(
+            Method::PUT,
+            Some(self.bucket_required()?),
+            None,
+            self.get_schema(),
+            payload,
+            None,
+            None,
+        );
+        rqst.add_acl(OSS_ACL, opts.acl);
+        for (key, val) in opts.to_opts() {
+            rqst.headers_mut()
+                .insert(key.parse::<HeaderName>()?, val.parse()?);
+        }
+        self.sign_and_dispatch(rqst).await?;
+        Ok(())
+    }
+
+    /// Delete the bucket of the client, which has to be empty.
+    pub async fn delete_bucket(&self) -> Result<()> {
+        let rqst = Request::new(
+            Method::DELETE,
+            Some(self.bucket_required()?),
+            None,
+            self.get_schema(),
+            None,
+            None,
+            None,
+        );
+        self.sign_and_dispatch(rqst).await?;
+        Ok(())
+    }
+
+    /// Owner, location, redundancy, versioning, ACL and default encryption of the bucket.
+    pub async fn get_bucket_info(&self) -> Result<BucketInfo> {
+        let doc: BucketInfoDocument = self.get_bucket_resource("bucketInfo").await?;
+        Ok(doc.bucket)
+    }
+
+    /// The region the bucket is located in, such as `oss-cn-hangzhou`.
+    pub async fn get_bucket_location(&self) -> Result<String> {
+        let doc: LocationConstraint = self.get_bucket_resource("location").await?;
+        Ok(doc.location)
+    }
+
+    /// Storage usage and object counts of the bucket.
+    pub async fn get_bucket_stat(&self) -> Result<BucketStat> {
+        self.get_bucket_resource("stat").await
+    }
+
+    /// The owner and canned ACL of the bucket.
+    pub async fn get_bucket_acl(&self) -> Result<AccessControlPolicy> {
+        self.get_bucket_resource("acl").await
+    }
+
+    /// Set the canned ACL of the bucket, `Acl::Default` only applies to objects.
+    pub async fn put_bucket_acl(&self, acl: Acl) -> Result<()> {
+        if acl == Acl::Default {
+            return Err(Error::invalid_argument("a bucket ACL can't be default"));
+        }
+        let mut rqst = self.bucket_resource_request(Method::PUT, "acl", None)?;
+        rqst.add_acl(OSS_ACL, Some(acl));
+        self.sign_and_dispatch(rqst).await?;
+        Ok(())
+    }
+
+    /// Replace the CORS rules of the bucket.
+    pub async fn put_bucket_cors(&self, cors: &CorsConfiguration) -> Result<()> {
+        self.put_bucket_resource("cors", cors.to_xml()).await
+    }
+
+    /// The CORS rules of the bucket, a NoSuchCORSConfiguration error if there are none.
+    pub async fn get_bucket_cors(&self) -> Result<CorsConfiguration> {
+        self.get_bucket_resource("cors").await
+    }
+
+    /// Remove all CORS rules of the bucket.
+    pub async fn delete_bucket_cors(&self) -> Result<()> {
+        self.delete_bucket_resource("cors").await
+    }
+
+    /// Replace the lifecycle rules of the bucket.
+    ///
+    /// The rules are checked for duplicate IDs, missing actions and dates that are not
+    /// midnight UTC before they are sent.
+    pub async fn put_bucket_lifecycle(&self, lifecycle: &LifecycleConfiguration) -> Result<()> {
+        lifecycle.validate()?;
+        self.put_bucket_resource("lifecycle", lifecycle.to_xml())
+            .await
+    }
+
+    /// The lifecycle rules of the bucket, a NoSuchLifecycle error if there are none.
+    pub async fn get_bucket_lifecycle(&self) -> Result<LifecycleConfiguration> {
+        self.get_bucket_resource("lifecycle").await
+    }
+
+    /// Remove all lifecycle rules of the bucket.
+    pub async fn delete_bucket_lifecycle(&self) -> Result<()> {
+        self.delete_bucket_resource("lifecycle").await
+    }
+
+    /// Write the access logs of the bucket to `logging.target_bucket`.
+    pub async fn put_bucket_logging(&self, logging: &BucketLogging) -> Result<()> {
+        self.put_bucket_resource("logging", logging.to_xml()).await
+    }
+
+    /// Where the access logs of the bucket are written, `None` if logging is off.
+    pub async fn get_bucket_logging(&self) -> Result<Option<BucketLogging>> {
+        let doc: BucketLoggingStatus = self.get_bucket_resource("logging").await?;
+        Ok(doc.logging_enabled)
+    }
+
+    /// Turn off access logging of the bucket.
+    pub async fn delete_bucket_logging(&self) -> Result<()> {
+        self.delete_bucket_resource("logging").await
+    }
+
+    /// Host a static website on the bucket.
+    pub async fn put_bucket_website(&self, website: &WebsiteConfiguration) -> Result<()> {
+        self.put_bucket_resource("website", website.to_xml()).await
+    }
+
+    /// The static website configuration of the bucket, a NoSuchWebsiteConfiguration error if
+    /// there is none.
+    pub async fn get_bucket_website(&self) -> Result<WebsiteConfiguration> {
+        self.get_bucket_resource("website").await
+    }
+
+    /// Turn off static website hosting of the bucket.
+    pub async fn delete_bucket_website(&self) -> Result<()> {
+        self.delete_bucket_resource("website").await
+    }
+
+    /// Set the hotlink protection of the bucket, an empty `referer_list` with
+    /// `allow_empty_referer` turns it off.
+    pub async fn put_bucket_referer(&self, referer: &RefererConfiguration) -> Result<()> {
+        self.put_bucket_resource("referer", referer.to_xml()).await
+    }
+
+    /// The hotlink protection of the bucket, which admits every referer by default.
+    pub async fn get_bucket_referer(&self) -> Result<RefererConfiguration> {
+        self.get_bucket_resource("referer").await
+    }
+
+    /// Set the access policy of the bucket, replacing any previous one.
+    ///
+    /// The policy is validated first, see `PolicyDocument::validate`.
+    pub async fn put_bucket_policy(&self, policy: &PolicyDocument) -> Result<()> {
+        policy.validate()?;
+        let payload = Payload::Buffer(policy.to_json().into());
+        let rqst = self.bucket_resource_request(Method::PUT, "policy", Some(payload))?;
+        self.sign_and_dispatch(rqst).await?;
+        Ok(())
+    }
+
+    /// The access policy of the bucket, a NoSuchBucketPolicy error if there is none.
+    pub async fn get_bucket_policy(&self) -> Result<PolicyDocument> {
+        let rqst = self.bucket_resource_request(Method::GET, "policy", None)?;
+        self.sign_and_dispatch(rqst).await?.into_json().await
+    }
+
+    /// Remove the access policy of the bucket.
+    pub async fn delete_bucket_policy(&self) -> Result<()> {
+        self.delete_bucket_resource("policy").await
+    }
+
+    /// A request on a sub-resource of the bucket, such as `?acl`.
+    fn bucket_resource_request(
+        &self,
+        method: Method,
+        resource: &str,
+        payload: Option<Payload>,
+    ) -> Result<Request<'_>> {
+        let mut params = Params::new();
+        params.insert(resource.to_owned(), None);
+        Ok(Request::new(
+            method,
+            Some(self.bucket_required()?),
+            None,
+            self.get_schema(),
+            payload,
+            Some(params),
+            None,
+        ))
+    }
+
+    /// GET a sub-resource of the bucket and parse the XML document returned.
+    async fn get_bucket_resource<T>(&self, resource: &str) -> Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let rqst = self.bucket_resource_request(Method::GET, resource, None)?;
+        self.sign_and_dispatch(rqst).await?.into_xml().await
+    }
+
+    /// PUT the XML document `xml` as a sub-resource of the bucket.
+    async fn put_bucket_resource(&self, resource: &str, xml: String) -> Result<()> {
+        let payload = Payload::Buffer(xml.into());
+        let rqst = self.bucket_resource_request(Method::PUT, resource, Some(payload))?;
+        self.sign_and_dispatch(rqst).await?;
+        Ok(())
+    }
+
+    /// DELETE a sub-resource of the bucket.
+    async fn delete_bucket_resource(&self, resource: &str) -> Result<()> {
+        let rqst = self.bucket_resource_request(Method::DELETE, resource, None)?;
+        self.sign_and_dispatch(rqst).await?;
+        Ok(())
+    }
+
+    /// The bucket of the client, for bucket-level requests.
+    pub(crate) fn bucket_required(&self) -> Result<&str> {
+        self.get_bucket()
+            .ok_or_else(|| Error::invalid_argument("the client has no bucket"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        CorsRule, Effect, ErrorDocument, ExternalRedirect, IndexDocument, LifecycleExpiration,
+        LifecycleRule, LifecycleTime, LifecycleTransition, Redirect, RoutingCondition, RoutingRule,
+        Statement, StorageClass,
+    };
+
+    #[tokio::test]
+    async fn bucket_test() {
+        let oss_cli = oss_client("rust-oss-sdk-bucket-test");
+        oss_cli.create_bucket(None).await.unwrap();
+        let opts = ListBucketsOptions {
+            prefix: Some("rust-oss-sdk-bucket-test".to_owned()),
+            ..Default::default()
+        };
+        let ret = oss_cli.list_buckets(opts).await.unwrap();
+        assert!(ret
+            .buckets
+            .iter()
+            .any(|bucket| bucket.name == "rust-oss-sdk-bucket-test"));
+        oss_cli.delete_bucket().await.unwrap();
+    }
+    #[tokio::test]
+    async fn bucket_info_test() {
+        let oss_cli = oss_client(&std::env::var("OSS_BUCKET").unwrap());
+        let info = oss_cli.get_bucket_info().await.unwrap();
+        assert_eq!(Some(info.name.as_str()), oss_cli.get_bucket());
+        let location = oss_cli.get_bucket_location().await.unwrap();
+        assert_eq!(location, info.location);
+        let stat = oss_cli.get_bucket_stat().await.unwrap();
+        assert!(stat.last_modified_time.is_some());
+        assert!(stat.usage(StorageClass::Standard).1 <= stat.object_count);
+    }
+    #[tokio::test]
+    async fn bucket_acl_test() {
+        let oss_cli = oss_client(&std::env::var("OSS_BUCKET").unwrap());
+        let acl = oss_cli.get_bucket_acl().await.unwrap().acl;
+        oss_cli.put_bucket_acl(Acl::PublicRead).await.unwrap();
+        assert_eq!(oss_cli.get_bucket_acl().await.unwrap().acl, Acl::PublicRead);
+        oss_cli.put_bucket_acl(acl).await.unwrap();
+        assert!(oss_cli.put_bucket_acl(Acl::Default).await.is_err());
+    }
+    #[tokio::test]
+    async fn bucket_cors_test() {
+        let oss_cli = oss_client(&std::env::var("OSS_BUCKET").unwrap());
+        let cors = CorsConfiguration {
+            rules: vec![CorsRule {
+                allowed_origins: vec!["https://example.com".to_owned()],
+                allowed_methods: vec![Method::GET, Method::PUT],
+                allowed_headers: vec!["*".to_owned()],
+                max_age_seconds: Some(100),
+                ..Default::default()
+            }],
+            response_vary: false,
+        };
+        oss_cli.put_bucket_cors(&cors).await.unwrap();
+        assert_eq!(oss_cli.get_bucket_cors().await.unwrap(), cors);
+        let preflight = oss_cli
+            .options_object("test-cors", "https://example.com", Method::PUT, &[])
+            .await
+            .unwrap();
+        assert!(preflight.allows("https://example.com", &Method::PUT));
+        let ret = oss_cli
+            .options_object("test-cors", "https://example.net", Method::PUT, &[])
+            .await;
+        assert!(ret.is_err());
+        oss_cli.delete_bucket_cors().await.unwrap();
+        assert!(oss_cli.get_bucket_cors().await.unwrap_err().is_not_found());
+    }
+    #[tokio::test]
+    async fn bucket_logging_test() {
+        let bucket = std::env::var("OSS_BUCKET").unwrap();
+        let oss_cli = oss_client(&bucket);
+        let logging = BucketLogging::new(bucket, "access-log/");
+        oss_cli.put_bucket_logging(&logging).await.unwrap();
+        assert_eq!(oss_cli.get_bucket_logging().await.unwrap(), Some(logging));
+        oss_cli.delete_bucket_logging().await.unwrap();
+        assert_eq!(oss_cli.get_bucket_logging().await.unwrap(), None);
+    }
+    #[tokio::test]
+    async fn bucket_website_test() {
+        let oss_cli = oss_client(&std::env::var("OSS_BUCKET").unwrap());
+        let website = WebsiteConfiguration {
+            index_document: Some(IndexDocument {
+                suffix: "index.html".to_owned(),
+                ..Default::default()
+            }),
+            error_document: Some(ErrorDocument {
+                key: "error.html".to_owned(),
+                http_status: Some(404),
+            }),
+            routing_rules: vec![RoutingRule {
+                rule_number: 1,
+                condition: RoutingCondition {
+                    key_prefix_equals: Some("old/".to_owned()),
+                    ..Default::default()
+                },
+                redirect: Redirect::External(ExternalRedirect {
+                    protocol: Some("https".to_owned()),
+                    host_name: Some("example.com".to_owned()),
+                    http_redirect_code: Some(301),
+                    ..Default::default()
+                }),
+            }],
+        };
+        oss_cli.put_bucket_website(&website).await.unwrap();
+        let ret = oss_cli.get_bucket_website().await.unwrap();
+        assert_eq!(ret.routing_rules, website.routing_rules);
+        oss_cli.delete_bucket_website().await.unwrap();
+        assert!(oss_cli
+            .get_bucket_website()
+            .await
+            .unwrap_err()
+            .is_not_found());
+    }
+    #[tokio::test]
+    async fn bucket_referer_test() {
+        let oss_cli = oss_client(&std::env::var("OSS_BUCKET").unwrap());
+        let referer = RefererConfiguration {
+            allow_empty_referer: false,
+            referer_list: vec!["https://*.example.com".to_owned()],
+            referer_blacklist: vec!["https://evil.example.com".to_owned()],
+            ..Default::default()
+        };
+        oss_cli.put_bucket_referer(&referer).await.unwrap();
+        assert_eq!(oss_cli.get_bucket_referer().await.unwrap(), referer);
+        oss_cli
+            .put_bucket_referer(&RefererConfiguration::default())
+            .await
+            .unwrap();
+        assert_eq!(
+            oss_cli.get_bucket_referer().await.unwrap(),
+            RefererConfiguration::default()
+        );
+    }
+    #[tokio::test]
+    async fn bucket_policy_test() {
+        let bucket = std::env::var("OSS_BUCKET").unwrap();
+        let oss_cli = oss_client(&bucket);
+        let policy = PolicyDocument::new(vec![Statement::new(
+            Effect::Deny,
+            vec!["oss:PutObject"],
+            vec![format!("acs:oss:*:*:{}/readonly/*", bucket)],
+        )
+        .with_principal("*")]);
+        oss_cli.put_bucket_policy(&policy).await.unwrap();
+        assert_eq!(oss_cli.get_bucket_policy().await.unwrap(), policy);
+        oss_cli.delete_bucket_policy().await.unwrap();
+        assert!(oss_cli
+            .get_bucket_policy()
+            .await
+            .unwrap_err()
+            .is_not_found());
+    }
+    #[tokio::test]
+    async fn bucket_lifecycle_test() {
+        let oss_cli = oss_client(&std::env::var("OSS_BUCKET").unwrap());
+        let lifecycle = LifecycleConfiguration::new(vec![LifecycleRule::new("tmp", "tmp/")
+            .with_transition(LifecycleTransition::new(
+                LifecycleTime::Days(30),
+                StorageClass::IA,
+            ))
+            .with_expiration(LifecycleExpiration::Days(90))
+            .with_abort_multipart_upload(LifecycleTime::Days(7))]);
+        oss_cli.put_bucket_lifecycle(&lifecycle).await.unwrap();
+        assert_eq!(oss_cli.get_bucket_lifecycle().await.unwrap(), lifecycle);
+        oss_cli.delete_bucket_lifecycle().await.unwrap();
+        assert!(oss_cli
+            .get_bucket_lifecycle()
+            .await
+            .unwrap_err()
+            .is_not_found());
+    }
+    #[tokio::test]
+    async fn create_bucket_default_acl_test() {
+        let oss_cli = OSSClient::new_with_default_client(
+            "北京",
+            None,
+            "bucket".to_owned(),
+            "key-id".to_owned(),
+            "key-secret".to_owned(),
+        );
+        let opts = CreateBucketOptions {
+            acl: Some(Acl::Default),
+            ..Default::default()
+        };
+        let err = oss_cli.create_bucket(opts).await.unwrap_err();
+        assert_eq!(err.kind(), crate::ErrorKind::InvalidArgument);
+    }
+    fn oss_client(bucket: &str) -> OSSClient<http_client::DefaultClient> {
+        let access_key_id = std::env::var("OSS_KEY_ID").unwrap();
+        let access_key_secret = std::env::var("OSS_KEY_SECRET").unwrap();
+
+        OSSClient::new_with_default_client(
+            "北京",
+            None,
+            bucket.to_owned(),
+            access_key_id,
+            access_key_secret,
+        )
+    }
+}
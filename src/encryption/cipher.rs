@@ -0,0 +1,120 @@
+use bytes::Bytes;
+use crypto::{
+    aessafe::AesSafe256EncryptorX8, blockmodes::CtrModeX8, symmetriccipher::SynchronousStreamCipher,
+};
+use rand::{rngs::OsRng, RngCore};
+
+use crate::{ByteStream, Payload};
+
+/// AES block size, the counter advances once per block.
+pub(crate) const BLOCK_SIZE: u64 = 16;
+
+/// The per-object data key and IV of an AES-256-CTR encrypted object.
+#[derive(Clone)]
+pub(crate) struct ContentCipher {
+    pub(crate) key: [u8; 32],
+    pub(crate) iv: [u8; 16],
+}
+
+impl ContentCipher {
+    /// A fresh random data key and IV.
+    pub(crate) fn generate() -> Self {
+        let mut key = [0u8; 32];
+        let mut iv = [0u8; 16];
+        OsRng.fill_bytes(&mut key);
+        OsRng.fill_bytes(&mut iv);
+        Self { key, iv }
+    }
+    pub(crate) fn from_parts(key: &[u8], iv: &[u8]) -> Option<Self> {
+        if key.len() != 32 || iv.len() != 16 {
+            return None;
+        }
+        let mut cipher = Self {
+            key: [0u8; 32],
+            iv: [0u8; 16],
+        };
+        cipher.key.copy_from_slice(key);
+        cipher.iv.copy_from_slice(iv);
+        Some(cipher)
+    }
+    /// A keystream starting at `offset`, which need not be a multiple of `BLOCK_SIZE`.
+    fn keystream_at(&self, offset: u64) -> CtrModeX8<AesSafe256EncryptorX8> {
+        let counter = u128::from_be_bytes(self.iv).wrapping_add((offset / BLOCK_SIZE) as u128);
+        let mut keystream = CtrModeX8::new(
+            AesSafe256EncryptorX8::new(&self.key),
+            &counter.to_be_bytes(),
+        );
+        // Use up the part of the first block before `offset`.
+        let lead = [0u8; BLOCK_SIZE as usize];
+        let lead = &lead[..(offset % BLOCK_SIZE) as usize];
+        keystream.process(lead, &mut vec![0u8; lead.len()]);
+        keystream
+    }
+    /// En- or decrypt `payload`, whose first byte sits at `offset` of the object.
+    pub(crate) fn apply_to_payload(&self, payload: Payload, offset: u64) -> Payload {
+        match payload {
+            Payload::Buffer(bytes) => {
+                let mut keystream = self.keystream_at(offset);
+                Payload::Buffer(apply(&mut keystream, &bytes))
+            }
+            payload => {
+                let cipher = self.clone();
+                payload.map_stream(move |stream| cipher.apply_to_stream(stream, offset))
+            }
+        }
+    }
+    /// En- or decrypt `stream`, whose first byte sits at `offset` of the object.
+    pub(crate) fn apply_to_stream(&self, stream: ByteStream, offset: u64) -> ByteStream {
+        let mut keystream = self.keystream_at(offset);
+        stream.map_chunks(move |chunk| apply(&mut keystream, &chunk))
+    }
+}
+
+fn apply(keystream: &mut CtrModeX8<AesSafe256EncryptorX8>, input: &[u8]) -> Bytes {
+    let mut output = vec![0u8; input.len()];
+    keystream.process(input, &mut output);
+    Bytes::from(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::TryStreamExt;
+
+    const PLAIN: &[u8] =
+        b"This is just a client-side encryption test, long enough for three blocks";
+
+    #[test]
+    fn apply_to_payload_test() {
+        let cipher = ContentCipher::generate();
+        let encrypted = match cipher.apply_to_payload(Payload::Buffer(PLAIN.into()), 0) {
+            Payload::Buffer(bytes) => bytes,
+            _ => unreachable!(),
+        };
+        assert_ne!(&encrypted[..], PLAIN);
+        let decrypted = match cipher.apply_to_payload(Payload::Buffer(encrypted), 0) {
+            Payload::Buffer(bytes) => bytes,
+            _ => unreachable!(),
+        };
+        assert_eq!(&decrypted[..], PLAIN);
+    }
+    #[tokio::test]
+    async fn ranged_decrypt_test() {
+        let cipher = ContentCipher::generate();
+        let encrypted = apply(&mut cipher.keystream_at(0), PLAIN);
+        // Decrypt bytes 20.., which start inside the second block.
+        let chunks = vec![Ok(encrypted.slice(20..30)), Ok(encrypted.slice(30..))];
+        let stream = ByteStream::new(futures::stream::iter(chunks));
+        let decrypted: Vec<Bytes> = cipher
+            .apply_to_stream(stream, 20)
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(decrypted.concat(), &PLAIN[20..]);
+        let decrypted = match cipher.apply_to_payload(Payload::Buffer(encrypted.slice(33..)), 33) {
+            Payload::Buffer(bytes) => bytes,
+            _ => unreachable!(),
+        };
+        assert_eq!(&decrypted[..], &PLAIN[33..]);
+    }
+}
@@ -0,0 +1,106 @@
+use async_trait::async_trait;
+use rand::rngs::OsRng;
+use rsa::{
+    pkcs1::DecodeRsaPrivateKey, pkcs8::DecodePrivateKey, PaddingScheme, PublicKey, RsaPrivateKey,
+    RsaPublicKey,
+};
+
+use crate::types::BoxedError;
+
+/// Wraps and unwraps the per-object data keys of an `EncryptionClient`.
+///
+/// Besides the bundled `RsaMasterKey`, this is the extension point for KMS backed master keys,
+/// which would use the `KMS/ALICLOUD` wrap algorithm.
+#[async_trait]
+pub trait MasterKeyProvider: Send + Sync {
+    /// Algorithm recorded in `x-oss-meta-client-side-encryption-wrap-alg`.
+    fn wrap_algorithm(&self) -> &str;
+    /// Description of the master key, recorded in `x-oss-meta-client-side-encryption-matdesc`.
+    fn material_description(&self) -> Option<&str> {
+        None
+    }
+    /// Encrypt a data key or IV with the master key.
+    async fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, BoxedError>;
+    /// Decrypt a data key or IV wrapped by `wrap`.
+    async fn unwrap(&self, ciphertext: &[u8]) -> Result<Vec<u8>, BoxedError>;
+}
+
+/// RSA master key using PKCS#1 v1.5 padding, as the official SDKs do.
+#[derive(Clone, Debug)]
+pub struct RsaMasterKey {
+    public_key: RsaPublicKey,
+    private_key: Option<RsaPrivateKey>,
+    description: Option<String>,
+}
+
+impl RsaMasterKey {
+    pub const WRAP_ALGORITHM: &'static str = "RSA/NONE/PKCS1Padding";
+
+    /// A master key able to both encrypt and decrypt.
+    pub fn new(private_key: RsaPrivateKey) -> Self {
+        Self {
+            public_key: RsaPublicKey::from(&private_key),
+            private_key: Some(private_key),
+            description: None,
+        }
+    }
+    /// A master key that can only encrypt, objects can't be read back with it.
+    pub fn encrypt_only(public_key: RsaPublicKey) -> Self {
+        Self {
+            public_key,
+            private_key: None,
+            description: None,
+        }
+    }
+    /// Load a PKCS#1 or PKCS#8 PEM encoded private key.
+    pub fn from_pem(pem: &str) -> Result<Self, BoxedError> {
+        let private_key = match RsaPrivateKey::from_pkcs1_pem(pem) {
+            Ok(key) => key,
+            Err(_) => RsaPrivateKey::from_pkcs8_pem(pem)?,
+        };
+        Ok(Self::new(private_key))
+    }
+    /// Set the material description stored along encrypted objects, usually a JSON object.
+    pub fn with_description<S: Into<String>>(mut self, description: S) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+#[async_trait]
+impl MasterKeyProvider for RsaMasterKey {
+    fn wrap_algorithm(&self) -> &str {
+        Self::WRAP_ALGORITHM
+    }
+    fn material_description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+    async fn wrap(&self, plaintext: &[u8]) -> Result<Vec<u8>, BoxedError> {
+        Ok(self
+            .public_key
+            .encrypt(&mut OsRng, PaddingScheme::new_pkcs1v15_encrypt(), plaintext)?)
+    }
+    async fn unwrap(&self, ciphertext: &[u8]) -> Result<Vec<u8>, BoxedError> {
+        let private_key = self
+            .private_key
+            .as_ref()
+            .ok_or("RSA master key has no private key")?;
+        Ok(private_key.decrypt(PaddingScheme::new_pkcs1v15_encrypt(), ciphertext)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rsa_wrap_test() {
+        let master_key = RsaMasterKey::new(RsaPrivateKey::new(&mut OsRng, 512).unwrap());
+        let wrapped = master_key.wrap(b"data key").await.unwrap();
+        assert_ne!(&wrapped[..], b"data key");
+        assert_eq!(master_key.unwrap(&wrapped).await.unwrap(), b"data key");
+
+        let encrypt_only = RsaMasterKey::encrypt_only(master_key.public_key.clone());
+        assert!(encrypt_only.unwrap(&wrapped).await.is_err());
+    }
+}
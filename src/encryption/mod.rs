@@ -0,0 +1,331 @@
+//! Client-side envelope encryption, compatible with the official OSS SDKs.
+//!
+//! Every object is encrypted with its own AES-256-CTR data key and IV. Both are wrapped by a
+//! `MasterKeyProvider` and stored base64 encoded in `x-oss-meta-client-side-encryption-*`
+//! metadata, the layout the Java and Go SDKs use.
+mod cipher;
+mod key;
+
+use base64::{decode, encode};
+use http_client::HttpClient;
+use hyper::HeaderMap;
+
+use crate::{
+    oss::OSSClient, ByteRange, CompletedPart, ContentRange, Error, GetObjectOptions,
+    InitiateMultipartUploadOptions, Metas, Payload, PutObjectOptions, Response, Result,
+    UploadPartOptions, OSS_META_PREFIX,
+};
+use cipher::{ContentCipher, BLOCK_SIZE};
+
+pub use key::{MasterKeyProvider, RsaMasterKey};
+
+const KEY: &str = "client-side-encryption-key";
+const START: &str = "client-side-encryption-start";
+const CEK_ALG: &str = "client-side-encryption-cek-alg";
+const WRAP_ALG: &str = "client-side-encryption-wrap-alg";
+const MATDESC: &str = "client-side-encryption-matdesc";
+const UNENCRYPTED_CONTENT_LENGTH: &str = "client-side-encryption-unencrypted-content-length";
+const UNENCRYPTED_CONTENT_MD5: &str = "client-side-encryption-unencrypted-content-md5";
+const DATA_SIZE: &str = "client-side-encryption-data-size";
+const PART_SIZE: &str = "client-side-encryption-part-size";
+
+const CEK_ALGORITHM: &str = "AES/CTR/NoPadding";
+
+/// An `OSSClient` that encrypts objects before upload and decrypts them on download.
+pub struct EncryptionClient<C: HttpClient, K: MasterKeyProvider> {
+    client: OSSClient<C>,
+    master_key: K,
+}
+
+/// A multipart upload started by `EncryptionClient::initiate_multipart_upload`.
+///
+/// It holds the data key of the object and has to be kept until the upload completes.
+#[derive(Clone)]
+pub struct EncryptedMultipartUpload {
+    upload_id: String,
+    data_size: u64,
+    part_size: u64,
+    cipher: ContentCipher,
+}
+
+impl EncryptedMultipartUpload {
+    pub fn upload_id(&self) -> &str {
+        &self.upload_id
+    }
+    pub fn part_size(&self) -> u64 {
+        self.part_size
+    }
+    /// Offset of part `part_number` in the object, checking that `len` is `part_size`, or the
+    /// rest of `data_size` for the last part.
+    fn part_offset(&self, part_number: u32, len: Option<usize>) -> Result<u64> {
+        if part_number == 0 {
+            return Err(Error::invalid_argument("part numbers start at 1"));
+        }
+        let offset = (part_number as u64 - 1) * self.part_size;
+        if offset >= self.data_size && !(offset == 0 && self.data_size == 0) {
+            return Err(Error::invalid_argument(format!(
+                "part {} starts past the data size {}",
+                part_number, self.data_size
+            )));
+        }
+        let expected = std::cmp::min(self.part_size, self.data_size - offset);
+        match len {
+            Some(len) if len as u64 == expected => Ok(offset),
+            Some(len) => Err(Error::invalid_argument(format!(
+                "part {} is {} bytes, expected {}",
+                part_number, len, expected
+            ))),
+            None => Err(Error::invalid_argument(
+                "encrypted parts need a payload of known length",
+            )),
+        }
+    }
+}
+
+impl<C: HttpClient, K: MasterKeyProvider> EncryptionClient<C, K> {
+    pub fn new(client: OSSClient<C>, master_key: K) -> Self {
+        Self { client, master_key }
+    }
+    /// The wrapped client, for requests that don't touch object content.
+    pub fn client(&self) -> &OSSClient<C> {
+        &self.client
+    }
+
+    /// Encrypt `payload` and upload it as `object`.
+    ///
+    /// A given `content_md5` describes the plaintext, it is stored as
+    /// `client-side-encryption-unencrypted-content-md5` rather than sent to OSS.
+    pub async fn put_object<S, Opts>(
+        &self,
+        object: S,
+        payload: Payload,
+        options: Opts,
+    ) -> Result<Response>
+    where
+        S: AsRef<str>,
+        Opts: Into<Option<PutObjectOptions>>,
+    {
+        let mut opts = options.into().unwrap_or_default();
+        let cipher = ContentCipher::generate();
+        let mut metas = opts.metas.take().unwrap_or_default();
+        self.seal_envelope(&cipher, &mut metas).await?;
+        if let Some(len) = payload.len() {
+            metas.insert(UNENCRYPTED_CONTENT_LENGTH.to_owned(), len.to_string());
+        }
+        if let Some(md5) = opts.content_md5.take() {
+            metas.insert(UNENCRYPTED_CONTENT_MD5.to_owned(), md5);
+        }
+        opts.metas = Some(metas);
+        let payload = cipher.apply_to_payload(payload, 0);
+        self.client.put_object(object, payload, opts).await
+    }
+
+    /// Download and decrypt `object`.
+    ///
+    /// Objects without client-side encryption metadata are returned as they are. Use
+    /// `get_object_range` rather than the `range` option for partial reads.
+    pub async fn get_object<S, Opts>(&self, object: S, options: Opts) -> Result<Response>
+    where
+        S: AsRef<str>,
+        Opts: Into<Option<GetObjectOptions>>,
+    {
+        let opts = options.into().unwrap_or_default();
+        if opts.range.is_some() {
+            return Err(Error::invalid_argument(
+                "use get_object_range for ranged reads of encrypted objects",
+            ));
+        }
+        let mut ret = self.client.get_object(object, opts).await?;
+        if let Some(cipher) = self.open_envelope(&ret.headers).await? {
+            ret.body = cipher.apply_to_stream(ret.body, 0);
+        }
+        Ok(ret)
+    }
+
+    /// Download and decrypt `range` of `object`, as `OSSClient::get_object_range` does.
+    ///
+    /// Decryption starts at the offset of the returned `Content-Range`, which need not be
+    /// aligned to an AES block.
+    pub async fn get_object_range<S, R, Opts>(
+        &self,
+        object: S,
+        range: R,
+        options: Opts,
+    ) -> Result<(ContentRange, Response)>
+    where
+        S: AsRef<str>,
+        R: Into<ByteRange>,
+        Opts: Into<Option<GetObjectOptions>>,
+    {
+        let (content_range, mut ret) = self.client.get_object_range(object, range, options).await?;
+        if let Some(cipher) = self.open_envelope(&ret.headers).await? {
+            ret.body = cipher.apply_to_stream(ret.body, content_range.start);
+        }
+        Ok((content_range, ret))
+    }
+
+    /// Start an encrypted multipart upload of `data_size` bytes, split into `part_size` parts.
+    ///
+    /// `part_size` has to be a multiple of 16 so that every part starts on an AES block.
+    pub async fn initiate_multipart_upload<S, Opts>(
+        &self,
+        object: S,
+        data_size: u64,
+        part_size: u64,
+        options: Opts,
+    ) -> Result<EncryptedMultipartUpload>
+    where
+        S: AsRef<str>,
+        Opts: Into<Option<InitiateMultipartUploadOptions>>,
+    {
+        if part_size == 0 || part_size & (BLOCK_SIZE - 1) != 0 {
+            return Err(Error::invalid_argument(format!(
+                "part size {} is not a multiple of {}",
+                part_size, BLOCK_SIZE
+            )));
+        }
+        let mut opts = options.into().unwrap_or_default();
+        let cipher = ContentCipher::generate();
+        let mut metas = opts.metas.take().unwrap_or_default();
+        self.seal_envelope(&cipher, &mut metas).await?;
+        metas.insert(DATA_SIZE.to_owned(), data_size.to_string());
+        metas.insert(PART_SIZE.to_owned(), part_size.to_string());
+        opts.metas = Some(metas);
+        let ret = self.client.initiate_multipart_upload(object, opts).await?;
+        Ok(EncryptedMultipartUpload {
+            upload_id: ret.upload_id,
+            data_size,
+            part_size,
+            cipher,
+        })
+    }
+
+    /// Encrypt and upload part `part_number`, every part but the last being `part_size` long.
+    ///
+    /// A part of another length, or of unknown length, is rejected, as it would shift the
+    /// keystream of the parts after it.
+    pub async fn upload_part<S, Opts>(
+        &self,
+        object: S,
+        upload: &EncryptedMultipartUpload,
+        part_number: u32,
+        payload: Payload,
+        options: Opts,
+    ) -> Result<Response>
+    where
+        S: AsRef<str>,
+        Opts: Into<Option<UploadPartOptions>>,
+    {
+        let offset = upload.part_offset(part_number, payload.len())?;
+        let payload = upload.cipher.apply_to_payload(payload, offset);
+        self.client
+            .upload_part(object, &upload.upload_id, part_number, payload, options)
+            .await
+    }
+
+    pub async fn complete_multipart_upload<S>(
+        &self,
+        object: S,
+        upload: &EncryptedMultipartUpload,
+        parts: &[CompletedPart],
+    ) -> Result<Response>
+    where
+        S: AsRef<str>,
+    {
+        self.client
+            .complete_multipart_upload(object, &upload.upload_id, parts)
+            .await
+    }
+
+    /// Wrap the data key and IV of `cipher` into `metas`.
+    async fn seal_envelope(&self, cipher: &ContentCipher, metas: &mut Metas) -> Result<()> {
+        let key = self
+            .master_key
+            .wrap(&cipher.key)
+            .await
+            .map_err(Error::encryption)?;
+        let iv = self
+            .master_key
+            .wrap(&cipher.iv)
+            .await
+            .map_err(Error::encryption)?;
+        metas.insert(KEY.to_owned(), encode(key));
+        metas.insert(START.to_owned(), encode(iv));
+        metas.insert(CEK_ALG.to_owned(), CEK_ALGORITHM.to_owned());
+        metas.insert(
+            WRAP_ALG.to_owned(),
+            self.master_key.wrap_algorithm().to_owned(),
+        );
+        if let Some(description) = self.master_key.material_description() {
+            metas.insert(MATDESC.to_owned(), description.to_owned());
+        }
+        Ok(())
+    }
+
+    /// Unwrap the data key and IV recorded in the response headers, if any.
+    async fn open_envelope(&self, headers: &HeaderMap) -> Result<Option<ContentCipher>> {
+        let meta = |name: &str| {
+            headers
+                .get(format!("{}{}", OSS_META_PREFIX, name).as_str())
+                .and_then(|val| val.to_str().ok())
+        };
+        let (key, iv) = match (meta(KEY), meta(START)) {
+            (Some(key), Some(iv)) => (key, iv),
+            _ => return Ok(None),
+        };
+        match meta(CEK_ALG) {
+            Some(CEK_ALGORITHM) | None => {}
+            Some(alg) => {
+                return Err(Error::encryption(format!(
+                    "unsupported content encryption algorithm {}",
+                    alg
+                )))
+            }
+        }
+        let key = decode(key).map_err(Error::encryption)?;
+        let iv = decode(iv).map_err(Error::encryption)?;
+        let key = self
+            .master_key
+            .unwrap(&key)
+            .await
+            .map_err(Error::encryption)?;
+        let iv = self
+            .master_key
+            .unwrap(&iv)
+            .await
+            .map_err(Error::encryption)?;
+        ContentCipher::from_parts(&key, &iv)
+            .map(Some)
+            .ok_or_else(|| Error::encryption("malformed data key or IV"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_offset_test() {
+        let upload = EncryptedMultipartUpload {
+            upload_id: "upload".to_owned(),
+            data_size: 100,
+            part_size: 32,
+            cipher: ContentCipher::generate(),
+        };
+        assert_eq!(upload.part_offset(1, Some(32)).unwrap(), 0);
+        assert_eq!(upload.part_offset(3, Some(32)).unwrap(), 64);
+        assert_eq!(upload.part_offset(4, Some(4)).unwrap(), 96);
+        assert!(upload.part_offset(0, Some(32)).is_err());
+        assert!(upload.part_offset(2, Some(31)).is_err());
+        assert!(upload.part_offset(4, Some(32)).is_err());
+        assert!(upload.part_offset(5, Some(0)).is_err());
+        assert!(upload.part_offset(1, None).is_err());
+
+        let empty = EncryptedMultipartUpload {
+            data_size: 0,
+            ..upload
+        };
+        assert_eq!(empty.part_offset(1, Some(0)).unwrap(), 0);
+        assert!(empty.part_offset(2, Some(0)).is_err());
+    }
+}
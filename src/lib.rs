@@ -1,5 +1,8 @@
 mod api;
 mod auth;
+mod bucket;
+pub mod encryption;
+mod multipart;
 mod oss;
 mod statics;
 mod types;
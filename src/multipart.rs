@@ -0,0 +1,403 @@
+use bytes::{Bytes, BytesMut};
+use futures::StreamExt;
+use http_client::{HttpClient, Params};
+use hyper::{
+    header::{HeaderName, ETAG},
+    Method,
+};
+
+use crate::{
+    api::{verify_upload, with_content_md5, wrap_upload},
+    oss::OSSClient,
+    types::complete_multipart_upload_xml,
+    ByteStream, CompletedPart, Crc64, Error, InitiateMultipartUploadOptions,
+    InitiateMultipartUploadResult, IntegrityError, Payload, ProgressEvent, PutObjectOptions,
+    Request, Response, Result, UploadPartOptions, OSS_TRAFFIC_LIMIT,
+};
+
+/// Part size of uploads of unknown length.
+pub(crate) const DEFAULT_PART_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Smallest part OSS accepts, but for the last part of an upload.
+pub(crate) const MIN_PART_SIZE: u64 = 100 * 1024;
+
+/// Most parts a multipart upload may have.
+const MAX_PARTS: u32 = 10_000;
+
+/// Splits a stream into parts of a fixed size, the last one possibly shorter.
+pub(crate) struct PartReader {
+    stream: ByteStream,
+    part_size: usize,
+    /// Bytes read past the end of the previous part
+    carry: Bytes,
+}
+
+impl PartReader {
+    pub(crate) fn new(stream: ByteStream, part_size: usize) -> Self {
+        Self {
+            stream,
+            part_size: part_size.max(1),
+            carry: Bytes::new(),
+        }
+    }
+    /// The next part, `None` once the stream is exhausted.
+    pub(crate) async fn next_part(&mut self) -> Result<Option<Bytes>> {
+        let mut part = BytesMut::new();
+        loop {
+            let wanted = self.part_size - part.len();
+            if self.carry.len() >= wanted {
+                part.extend_from_slice(&self.carry.split_to(wanted));
+                return Ok(Some(part.freeze()));
+            }
+            part.extend_from_slice(&std::mem::take(&mut self.carry));
+            match self.stream.next().await {
+                Some(chunk) => self.carry = chunk?,
+                None if part.is_empty() => return Ok(None),
+                None => return Ok(Some(part.freeze())),
+            }
+        }
+    }
+    /// Whether the stream is exhausted, reading ahead if needed.
+    pub(crate) async fn at_end(&mut self) -> Result<bool> {
+        while self.carry.is_empty() {
+            match self.stream.next().await {
+                Some(chunk) => self.carry = chunk?,
+                None => return Ok(true),
+            }
+        }
+        Ok(false)
+    }
+}
+
+impl<C: HttpClient> OSSClient<C> {
+    /// Start a multipart upload of `object`, returning the ID its parts are uploaded under.
+    pub async fn initiate_multipart_upload<S, Opts>(
+        &self,
+        object: S,
+        options: Opts,
+    ) -> Result<InitiateMultipartUploadResult>
+    where
+        S: AsRef<str>,
+        Opts: Into<Option<InitiateMultipartUploadOptions>>,
+    {
+        let mut params = Params::new();
+        params.insert("uploads".to_owned(), None);
+        let mut rqst = Request::new(
+            Method::POST,
+            self.get_bucket(),
+            Some(object.as_ref()),
+            self.get_schema(),
+            None,
+            Some(params),
+            None,
+        );
+        let opts = options.into().unwrap_or_default();
+        rqst.add_metas(opts.metas.as_ref())?;
+        rqst.add_server_side_encryption(opts.server_side_encryption.as_ref())?;
+        for (key, val) in opts.to_opts() {
+            rqst.headers_mut()
+                .insert(key.parse::<HeaderName>()?, val.parse()?);
+        }
+        self.sign_and_dispatch(rqst).await?.into_xml().await
+    }
+
+    /// Upload part `part_number` (1 to 10000) of a multipart upload.
+    ///
+    /// The ETag header of the response identifies the part in `complete_multipart_upload`.
+    pub async fn upload_part<S, Opts>(
+        &self,
+        object: S,
+        upload_id: &str,
+        part_number: u32,
+        payload: Payload,
+        options: Opts,
+    ) -> Result<Response>
+    where
+        S: AsRef<str>,
+        Opts: Into<Option<UploadPartOptions>>,
+    {
+        let mut opts = options.into().unwrap_or_default();
+        let payload =
+            with_content_md5(payload, &mut opts.content_md5, opts.compute_content_md5).await?;
+        let size = payload.len().unwrap_or_default() as u64;
+        let (payload, crc) = wrap_upload(
+            payload,
+            opts.check_crc64.unwrap_or(true),
+            opts.rate_limiter.clone(),
+            opts.progress.clone(),
+        );
+        let mut params = Params::new();
+        params.insert("partNumber".to_owned(), Some(part_number.to_string()));
+        params.insert("uploadId".to_owned(), Some(upload_id.to_owned()));
+        let mut rqst = Request::new(
+            Method::PUT,
+            self.get_bucket(),
+            Some(object.as_ref()),
+            self.get_schema(),
+            Some(payload),
+            Some(params),
+            None,
+        );
+        for (key, val) in opts.to_opts() {
+            rqst.headers_mut()
+                .insert(key.parse::<HeaderName>()?, val.parse()?);
+        }
+        if let Some(limit) = opts.traffic_limit {
+            rqst.headers_mut()
+                .insert(OSS_TRAFFIC_LIMIT, limit.bits_per_second().into());
+        }
+        rqst.set_progress(opts.progress.clone());
+        let ret = self.sign_and_dispatch(rqst).await?;
+        verify_upload(&ret, crc.as_ref())?;
+        if let Some(progress) = opts.progress {
+            progress.emit(ProgressEvent::PartCompleted { part_number, size });
+        }
+        Ok(ret)
+    }
+
+    /// Assemble the uploaded `parts`, in ascending part number order, into the object.
+    pub async fn complete_multipart_upload<S>(
+        &self,
+        object: S,
+        upload_id: &str,
+        parts: &[CompletedPart],
+    ) -> Result<Response>
+    where
+        S: AsRef<str>,
+    {
+        let mut params = Params::new();
+        params.insert("uploadId".to_owned(), Some(upload_id.to_owned()));
+        let payload = Payload::Buffer(complete_multipart_upload_xml(parts).into());
+        let rqst = Request::new(
+            Method::POST,
+            self.get_bucket(),
+            Some(object.as_ref()),
+            self.get_schema(),
+            Some(payload),
+            Some(params),
+            None,
+        );
+        self.sign_and_dispatch(rqst).await
+    }
+
+    /// Upload `first` and the rest of `reader` as the parts of a multipart upload of `object`.
+    ///
+    /// The upload is aborted if any part fails.
+    pub(crate) async fn put_object_in_parts(
+        &self,
+        object: &str,
+        first: Bytes,
+        reader: PartReader,
+        opts: PutObjectOptions,
+    ) -> Result<Response> {
+        if opts.content_md5.is_some() {
+            return Err(Error::invalid_argument(
+                "content_md5 can't be set on an upload sent in parts",
+            ));
+        }
+        let init_opts = InitiateMultipartUploadOptions {
+            cache_control: opts.cache_control.clone(),
+            content_disposition: opts.content_disposition.clone(),
+            content_encoding: opts.content_encoding.clone(),
+            content_type: opts.content_type.clone(),
+            expires: opts.expires.clone(),
+            x_oss_forbid_overwrite: opts.x_oss_forbid_overwrite.clone(),
+            x_oss_storage_class: opts.x_oss_storage_class.clone(),
+            x_oss_tagging: opts.x_oss_tagging.clone(),
+            metas: opts.metas.clone(),
+            server_side_encryption: opts.server_side_encryption.clone(),
+        };
+        let upload_id = self
+            .initiate_multipart_upload(object, init_opts)
+            .await?
+            .upload_id;
+        let ret = self
+            .upload_parts(object, &upload_id, first, reader, &opts)
+            .await;
+        if ret.is_err() {
+            // The original error matters more than a failure to clean up.
+            let _ = self.abort_multipart_upload(object, &upload_id).await;
+        }
+        let ret = ret?;
+        // InitiateMultipartUpload takes no ACL, so it is set on the completed object.
+        if let Some(acl) = opts.object_acl {
+            self.put_object_acl(object, acl).await?;
+        }
+        Ok(ret)
+    }
+    async fn upload_parts(
+        &self,
+        object: &str,
+        upload_id: &str,
+        first: Bytes,
+        mut reader: PartReader,
+        opts: &PutObjectOptions,
+    ) -> Result<Response> {
+        let progress = opts.progress.as_ref();
+        if let Some(progress) = progress {
+            progress.emit(ProgressEvent::Started { total: None });
+        }
+        let mut parts = vec![];
+        let mut crc = 0;
+        let mut transferred = 0;
+        let mut next = Some(first);
+        while let Some(part) = next.take() {
+            let part_number = parts.len() as u32 + 1;
+            if part_number > MAX_PARTS {
+                return Err(Error::invalid_argument(format!(
+                    "more than {} parts, raise part_size",
+                    MAX_PARTS
+                )));
+            }
+            let size = part.len() as u64;
+            crc = Crc64::combine(crc, Crc64::checksum(&part), size);
+            let part_opts = UploadPartOptions {
+                compute_content_md5: opts.compute_content_md5,
+                check_crc64: opts.check_crc64,
+                rate_limiter: opts.rate_limiter.clone(),
+                traffic_limit: opts.traffic_limit,
+                ..Default::default()
+            };
+            let ret = self
+                .upload_part(
+                    object,
+                    upload_id,
+                    part_number,
+                    Payload::Buffer(part),
+                    part_opts,
+                )
+                .await?;
+            let e_tag = ret
+                .headers
+                .get(ETAG)
+                .ok_or_else(|| Error::header_to_str_error("part uploaded without ETag"))?
+                .to_str()
+                .map_err(Error::header_to_str_error)?
+                .to_owned();
+            parts.push(CompletedPart { part_number, e_tag });
+            transferred += size;
+            if let Some(progress) = progress {
+                progress.emit(ProgressEvent::Transferred {
+                    transferred,
+                    total: None,
+                });
+                progress.emit(ProgressEvent::PartCompleted { part_number, size });
+            }
+            next = reader.next_part().await?;
+        }
+        let ret = self
+            .complete_multipart_upload(object, upload_id, &parts)
+            .await?;
+        if let (true, Some(expected)) = (opts.check_crc64.unwrap_or(true), ret.crc64()) {
+            if expected != crc {
+                return Err(IntegrityError {
+                    expected,
+                    actual: crc,
+                }
+                .into());
+            }
+        }
+        if let Some(progress) = progress {
+            progress.emit(ProgressEvent::Completed { transferred });
+        }
+        Ok(ret)
+    }
+
+    /// Cancel a multipart upload and discard the parts uploaded so far.
+    pub async fn abort_multipart_upload<S>(&self, object: S, upload_id: &str) -> Result<Response>
+    where
+        S: AsRef<str>,
+    {
+        let mut params = Params::new();
+        params.insert("uploadId".to_owned(), Some(upload_id.to_owned()));
+        let rqst = Request::new(
+            Method::DELETE,
+            self.get_bucket(),
+            Some(object.as_ref()),
+            self.get_schema(),
+            None,
+            Some(params),
+            None,
+        );
+        self.sign_and_dispatch(rqst).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    #[tokio::test]
+    async fn multipart_upload_test() {
+        let oss_cli = oss_client();
+        let object = "test-multipart";
+        let upload = oss_cli
+            .initiate_multipart_upload(object, None)
+            .await
+            .unwrap();
+        let payload = Payload::Buffer("This is just a multipart test".into());
+        let ret = oss_cli
+            .upload_part(object, &upload.upload_id, 1, payload, None)
+            .await
+            .unwrap();
+        assert!(ret.status.is_success());
+        let e_tag = ret.headers["etag"].to_str().unwrap().to_owned();
+        let parts = [CompletedPart {
+            part_number: 1,
+            e_tag,
+        }];
+        let ret = oss_cli
+            .complete_multipart_upload(object, &upload.upload_id, &parts)
+            .await
+            .unwrap();
+        assert!(ret.status.is_success());
+    }
+    #[tokio::test]
+    async fn part_reader_test() {
+        let chunks = vec![&b"0123"[..], b"", b"45678", b"9"];
+        let stream = futures::stream::iter(chunks.into_iter().map(|c| Ok(Bytes::from(c))));
+        let mut reader = PartReader::new(ByteStream::new(stream), 3);
+        let mut parts = vec![];
+        while !reader.at_end().await.unwrap() {
+            parts.push(reader.next_part().await.unwrap().unwrap());
+        }
+        assert_eq!(parts, vec!["012", "345", "678", "9"]);
+        assert!(reader.next_part().await.unwrap().is_none());
+    }
+    #[tokio::test]
+    async fn unsized_put_options_test() {
+        let oss_cli = OSSClient::new_with_default_client(
+            "北京",
+            None,
+            "bucket".to_owned(),
+            "key-id".to_owned(),
+            "key-secret".to_owned(),
+        );
+        let payload = || {
+            let chunks = (0..4).map(|_| Ok(Bytes::from(vec![0; 64 * 1024])));
+            Payload::Stream(ByteStream::new(futures::stream::iter(chunks)))
+        };
+        // Both are rejected before anything is sent.
+        let opts = PutObjectOptions {
+            part_size: Some(64),
+            ..Default::default()
+        };
+        let err = oss_cli.put_object("object", payload(), opts).await;
+        assert!(matches!(err, Err(err) if err.kind() == ErrorKind::InvalidArgument));
+        let opts = PutObjectOptions {
+            content_md5: Some("eB5eJF1ptWaXm4bijSPyxw==".to_owned()),
+            part_size: Some(MIN_PART_SIZE),
+            ..Default::default()
+        };
+        let err = oss_cli.put_object("object", payload(), opts).await;
+        assert!(matches!(err, Err(err) if err.kind() == ErrorKind::InvalidArgument));
+    }
+    fn oss_client() -> OSSClient<http_client::DefaultClient> {
+        let bucket = std::env::var("OSS_BUCKET").unwrap();
+        let access_key_id = std::env::var("OSS_KEY_ID").unwrap();
+        let access_key_secret = std::env::var("OSS_KEY_SECRET").unwrap();
+
+        OSSClient::new_with_default_client("北京", None, bucket, access_key_id, access_key_secret)
+    }
+}
@@ -5,12 +5,13 @@ use hyper::header::HeaderValue;
 use url::Url;
 
 use std::{collections::BTreeMap, str::FromStr};
+use tokio::time::sleep;
 
 use crate::{
     auth::canonicalized_resource,
     statics::{CONTENT_MD5, CONTENT_TYPE, OSS_CANONICALIZED_PREFIX},
-    types::{Region, Request, Result, Schema},
-    Response,
+    types::{Error, Region, Request, Result, RetryPolicy, Schema},
+    ProgressEvent, Response,
 };
 
 #[derive(Debug)]
@@ -21,6 +22,7 @@ pub struct OSSClient<C: HttpClient> {
     access_key_secret: String,
     bucket: Option<String>,
     schema: Schema,
+    retry_policy: RetryPolicy,
 }
 
 impl<C: HttpClient> OSSClient<C> {
@@ -49,8 +51,17 @@ impl<C: HttpClient> OSSClient<C> {
             bucket: bucket.into().map(|bucket| bucket.to_string()),
             access_key_id: access_key_id.into(),
             access_key_secret: access_key_secret.into(),
+            retry_policy: RetryPolicy::default(),
         }
     }
+    /// Replace the `RetryPolicy` requests are sent with.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+    pub fn get_retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
     pub fn get_bucket(&self) -> Option<&str> {
         self.bucket.as_ref().map(String::as_str)
     }
@@ -95,11 +106,11 @@ impl<C: HttpClient> OSSClient<C> {
             "OSSAccessKeyId={}&Expires={}&Signature={}",
             self.access_key_id, expires, sign_str_base64
         );
-        self.host(object, &auth_params)
+        self.host(self.get_bucket(), object, &auth_params)
     }
-    fn host(&self, object: Option<&str>, params_str: &str) -> String {
+    fn host(&self, bucket: Option<&str>, object: Option<&str>, params_str: &str) -> String {
         let mut host = format!("{}://", self.schema);
-        if let Some(bucket) = self.get_bucket() {
+        if let Some(bucket) = bucket {
             host.push_str(bucket);
             host.push('.');
         }
@@ -127,15 +138,51 @@ impl<C: HttpClient> OSSClient<C> {
         self.oss_sign(&mut rqst)?;
         self.generate_http_request(rqst)
     }
+    /// Sign and send `rqst`, turning error responses into `ServiceError`s.
+    ///
+    /// Failed attempts are retried as the `RetryPolicy` allows, each signed anew.
     pub(crate) async fn sign_and_dispatch<'a>(&self, mut rqst: Request<'a>) -> Result<Response> {
-        let len = rqst.content_length();
+        // Guessing a length would get the upload truncated or rejected.
+        let len = rqst.content_length().ok_or_else(|| {
+            Error::invalid_argument(
+                "payload of unknown length, give the stream a size or upload it with put_object",
+            )
+        })?;
         rqst.headers_mut()
             .insert("content-length", HeaderValue::from(len));
+        let mut attempt = 1;
+        loop {
+            // Taken before the payload is consumed, `None` if it can't be sent again.
+            let next = if attempt < self.retry_policy.max_attempts() {
+                rqst.try_clone()
+            } else {
+                None
+            };
+            let err = match self.dispatch_once(rqst).await {
+                Ok(ret) => return Ok(ret),
+                Err(err) => err,
+            };
+            match next {
+                Some(next) if self.retry_policy.should_retry(attempt, &err) => {
+                    sleep(self.retry_policy.backoff(attempt)).await;
+                    if let Some(progress) = next.progress() {
+                        progress.emit(ProgressEvent::Retried { attempt });
+                    }
+                    rqst = next;
+                    attempt += 1;
+                }
+                _ => return Err(err),
+            }
+        }
+    }
+    async fn dispatch_once<'a>(&self, rqst: Request<'a>) -> Result<Response> {
         let request = self.singed_request(rqst)?;
-        Ok(self.client.dispatch(request).await?.into())
+        Response::from(self.client.dispatch(request).await?)
+            .error_for_status()
+            .await
     }
     pub(crate) fn generate_http_request(&self, mut rqst: Request) -> Result<HttpRequest> {
-        let mut url = Url::from_str(&self.host(rqst.get_object(), ""))?;
+        let mut url = Url::from_str(&self.host(rqst.get_bucket(), rqst.get_object(), ""))?;
         let mut query = url.query_pairs_mut();
         for (name, value) in rqst.get_params() {
             if let Some(value) = value {
@@ -180,6 +227,7 @@ impl OSSClient<http_client::DefaultClient> {
             bucket: bucket.into(),
             access_key_id: access_key_id.into(),
             access_key_secret: access_key_secret.into(),
+            retry_policy: RetryPolicy::default(),
         }
     }
 }
@@ -5,7 +5,15 @@ pub(crate) const CONTENT_TYPE: &str = "content-type";
 
 pub(crate) const CONTENT_MD5: &str = "Content-MD5";
 
-pub(crate) const RESOURCES: [&str; 51] = [
+pub(crate) const OSS_HASH_CRC64: &str = "x-oss-hash-crc64ecma";
+
+pub(crate) const OSS_TRAFFIC_LIMIT: &str = "x-oss-traffic-limit";
+
+pub(crate) const OSS_COPY_SOURCE: &str = "x-oss-copy-source";
+
+pub(crate) const OSS_RANGE_BEHAVIOR: &str = "x-oss-range-behavior";
+
+pub(crate) const RESOURCES: [&str; 53] = [
     "acl",
     "uploads",
     "location",
@@ -57,4 +65,6 @@ pub(crate) const RESOURCES: [&str; 51] = [
     "callback",
     "callback-var",
     "continuation-token",
+    "stat",
+    "policy",
 ];
@@ -0,0 +1,106 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Deserializer};
+
+use super::{xml::deserialize_from_str, Owner};
+
+pub(crate) const OSS_ACL: &str = "x-oss-acl";
+pub(crate) const OSS_OBJECT_ACL: &str = "x-oss-object-acl";
+
+/// Canned access control lists of buckets and objects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Acl {
+    /// Only the owner and authorized users may read and write
+    Private,
+    /// Anyone may read, only the owner and authorized users may write
+    PublicRead,
+    /// Anyone may read and write
+    PublicReadWrite,
+    /// Objects only, the ACL of the bucket applies
+    Default,
+}
+
+impl Acl {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Acl::Private => "private",
+            Acl::PublicRead => "public-read",
+            Acl::PublicReadWrite => "public-read-write",
+            Acl::Default => "default",
+        }
+    }
+}
+
+impl FromStr for Acl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "private" => Ok(Acl::Private),
+            "public-read" => Ok(Acl::PublicRead),
+            "public-read-write" => Ok(Acl::PublicReadWrite),
+            "default" => Ok(Acl::Default),
+            _ => Err(format!("unknown acl {:?}", s)),
+        }
+    }
+}
+
+deserialize_from_str!(Acl);
+
+impl fmt::Display for Acl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Result of `get_bucket_acl`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct AccessControlPolicy {
+    pub owner: Owner,
+    #[serde(rename = "AccessControlList", deserialize_with = "grant")]
+    pub acl: Acl,
+}
+
+/// The ACL granted by an `<AccessControlList>` element.
+pub(crate) fn grant<'de, D>(deserializer: D) -> Result<Acl, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct AccessControlList {
+        #[serde(rename = "Grant")]
+        grant: Acl,
+    }
+    Ok(AccessControlList::deserialize(deserializer)?.grant)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_control_policy_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<AccessControlPolicy>
+    <Owner>
+        <ID>0022012****</ID>
+        <DisplayName>user_example</DisplayName>
+    </Owner>
+    <AccessControlList>
+        <Grant>public-read</Grant>
+    </AccessControlList>
+</AccessControlPolicy>"#;
+        let ret: AccessControlPolicy = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(ret.owner.id, "0022012****");
+        assert_eq!(ret.acl, Acl::PublicRead);
+        for acl in [
+            Acl::Private,
+            Acl::PublicRead,
+            Acl::PublicReadWrite,
+            Acl::Default,
+        ] {
+            assert_eq!(acl.to_string().parse::<Acl>(), Ok(acl));
+        }
+    }
+}
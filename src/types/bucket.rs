@@ -0,0 +1,437 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{Deserialize, Deserializer};
+
+use super::{
+    acl::grant,
+    xml::{deserialize_from_str, escape},
+    Acl, ServerSideEncryption, StorageClass,
+};
+
+/// How the data of a bucket is replicated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DataRedundancyType {
+    /// Locally redundant storage, copies within a single zone
+    LRS,
+    /// Zone-redundant storage, copies across the zones of the region
+    ZRS,
+}
+
+impl DataRedundancyType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataRedundancyType::LRS => "LRS",
+            DataRedundancyType::ZRS => "ZRS",
+        }
+    }
+}
+
+impl FromStr for DataRedundancyType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "LRS" => Ok(DataRedundancyType::LRS),
+            "ZRS" => Ok(DataRedundancyType::ZRS),
+            _ => Err(format!("unknown data redundancy type {:?}", s)),
+        }
+    }
+}
+
+/// Versioning state of a bucket.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum VersioningStatus {
+    Enabled,
+    /// Versioning was enabled and has been suspended, existing versions are kept
+    Suspended,
+}
+
+impl FromStr for VersioningStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Enabled" => Ok(VersioningStatus::Enabled),
+            "Suspended" => Ok(VersioningStatus::Suspended),
+            _ => Err(format!("unknown versioning status {:?}", s)),
+        }
+    }
+}
+
+deserialize_from_str!(DataRedundancyType, VersioningStatus);
+
+/// The owner of a bucket.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct Owner {
+    #[serde(rename = "ID")]
+    pub id: String,
+    #[serde(rename = "DisplayName")]
+    pub display_name: String,
+}
+
+/// A bucket as listed by `list_buckets`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BucketSummary {
+    pub name: String,
+    pub creation_date: DateTime<Utc>,
+    /// The region the bucket is located in, such as `oss-cn-hangzhou`
+    pub location: String,
+    /// The region ID, such as `cn-hangzhou`
+    pub region: Option<String>,
+    pub extranet_endpoint: Option<String>,
+    pub intranet_endpoint: Option<String>,
+    pub storage_class: StorageClass,
+}
+
+/// Result of `list_buckets`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ListBucketsResult {
+    pub prefix: Option<String>,
+    pub marker: Option<String>,
+    pub max_keys: Option<u32>,
+    /// Whether more buckets follow, to be listed from `next_marker`
+    #[serde(default)]
+    pub is_truncated: bool,
+    pub next_marker: Option<String>,
+    pub owner: Owner,
+    #[serde(default, deserialize_with = "bucket_list")]
+    pub buckets: Vec<BucketSummary>,
+}
+
+fn bucket_list<'de, D>(deserializer: D) -> Result<Vec<BucketSummary>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Buckets {
+        #[serde(rename = "Bucket", default)]
+        bucket: Vec<BucketSummary>,
+    }
+    Ok(Buckets::deserialize(deserializer)?.bucket)
+}
+
+/// Result of `get_bucket_info`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BucketInfo {
+    pub name: String,
+    pub creation_date: DateTime<Utc>,
+    /// The region the bucket is located in, such as `oss-cn-hangzhou`
+    pub location: String,
+    pub extranet_endpoint: Option<String>,
+    pub intranet_endpoint: Option<String>,
+    pub storage_class: StorageClass,
+    pub data_redundancy_type: Option<DataRedundancyType>,
+    /// `None` if versioning was never enabled
+    pub versioning: Option<VersioningStatus>,
+    pub owner: Owner,
+    #[serde(rename = "AccessControlList", deserialize_with = "grant")]
+    pub acl: Acl,
+    /// The default encryption of objects uploaded to the bucket
+    #[serde(
+        rename = "ServerSideEncryptionRule",
+        default,
+        deserialize_with = "server_side_encryption_rule"
+    )]
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    pub resource_group_id: Option<String>,
+    pub comment: Option<String>,
+}
+
+/// The document `get_bucket_info` parses, wrapping a `BucketInfo`.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct BucketInfoDocument {
+    pub(crate) bucket: BucketInfo,
+}
+
+fn server_side_encryption_rule<'de, D>(
+    deserializer: D,
+) -> Result<Option<ServerSideEncryption>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Rule {
+        #[serde(rename = "SSEAlgorithm")]
+        algorithm: String,
+        #[serde(rename = "KMSMasterKeyID")]
+        key_id: Option<String>,
+        #[serde(rename = "KMSDataEncryption")]
+        data_encryption: Option<String>,
+    }
+    let rule = Rule::deserialize(deserializer)?;
+    Ok(ServerSideEncryption::from_parts(
+        &rule.algorithm,
+        rule.key_id.as_deref(),
+        rule.data_encryption.as_deref(),
+    ))
+}
+
+/// Result of `get_bucket_location`.
+#[derive(Deserialize)]
+pub(crate) struct LocationConstraint {
+    #[serde(rename = "$value")]
+    pub(crate) location: String,
+}
+
+/// Result of `get_bucket_stat`, storage in bytes and object counts of a bucket.
+///
+/// The statistics are not real time, they may lag behind by an hour or more.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+pub struct BucketStat {
+    /// Total storage of the bucket
+    pub storage: u64,
+    pub object_count: u64,
+    /// Multipart uploads initiated but neither completed nor aborted
+    pub multipart_upload_count: u64,
+    pub live_channel_count: u64,
+    /// When the statistics were taken
+    #[serde(deserialize_with = "unix_time")]
+    pub last_modified_time: Option<DateTime<Utc>>,
+    pub standard_storage: u64,
+    pub standard_object_count: u64,
+    /// Billed storage of Infrequent Access objects, small objects count as 64 KiB
+    pub infrequent_access_storage: u64,
+    /// Actual storage of Infrequent Access objects
+    pub infrequent_access_real_storage: u64,
+    pub infrequent_access_object_count: u64,
+    pub archive_storage: u64,
+    pub archive_real_storage: u64,
+    pub archive_object_count: u64,
+    pub cold_archive_storage: u64,
+    pub cold_archive_real_storage: u64,
+    pub cold_archive_object_count: u64,
+    pub deep_cold_archive_storage: u64,
+    pub deep_cold_archive_real_storage: u64,
+    pub deep_cold_archive_object_count: u64,
+}
+
+impl BucketStat {
+    /// Billed storage and object count of the objects of `storage_class`.
+    pub fn usage(&self, storage_class: StorageClass) -> (u64, u64) {
+        match storage_class {
+            StorageClass::Standard => (self.standard_storage, self.standard_object_count),
+            StorageClass::IA => (
+                self.infrequent_access_storage,
+                self.infrequent_access_object_count,
+            ),
+            StorageClass::Archive => (self.archive_storage, self.archive_object_count),
+            StorageClass::ColdArchive => {
+                (self.cold_archive_storage, self.cold_archive_object_count)
+            }
+            StorageClass::DeepColdArchive => (
+                self.deep_cold_archive_storage,
+                self.deep_cold_archive_object_count,
+            ),
+        }
+    }
+}
+
+fn unix_time<'de, D>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let secs = i64::deserialize(deserializer)?;
+    Ok(Utc.timestamp_opt(secs, 0).single())
+}
+
+/// Body of a PutBucket request.
+pub(crate) fn create_bucket_xml(
+    storage_class: Option<StorageClass>,
+    data_redundancy_type: Option<DataRedundancyType>,
+) -> String {
+    let mut xml = String::from("<CreateBucketConfiguration>");
+    if let Some(storage_class) = storage_class {
+        xml += &format!(
+            "<StorageClass>{}</StorageClass>",
+            escape(storage_class.as_str())
+        );
+    }
+    if let Some(redundancy) = data_redundancy_type {
+        xml += &format!(
+            "<DataRedundancyType>{}</DataRedundancyType>",
+            redundancy.as_str()
+        );
+    }
+    xml += "</CreateBucketConfiguration>";
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn list_buckets_result_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ListAllMyBucketsResult>
+  <Prefix>my</Prefix>
+  <Marker>mybucket</Marker>
+  <MaxKeys>10</MaxKeys>
+  <IsTruncated>true</IsTruncated>
+  <NextMarker>mybucket10</NextMarker>
+  <Owner>
+    <ID>512**</ID>
+    <DisplayName>51264</DisplayName>
+  </Owner>
+  <Buckets>
+    <Bucket>
+      <CreationDate>2014-05-14T11:18:32.000Z</CreationDate>
+      <ExtranetEndpoint>oss-cn-hangzhou.aliyuncs.com</ExtranetEndpoint>
+      <IntranetEndpoint>oss-cn-hangzhou-internal.aliyuncs.com</IntranetEndpoint>
+      <Location>oss-cn-hangzhou</Location>
+      <Name>mybucket01</Name>
+      <Region>cn-hangzhou</Region>
+      <StorageClass>Standard</StorageClass>
+    </Bucket>
+    <Bucket>
+      <CreationDate>2014-05-14T11:18:33.000Z</CreationDate>
+      <Location>oss-cn-beijing</Location>
+      <Name>mybucket02</Name>
+      <StorageClass>IA</StorageClass>
+    </Bucket>
+  </Buckets>
+</ListAllMyBucketsResult>"#;
+        let ret: ListBucketsResult = quick_xml::de::from_str(xml).unwrap();
+        assert!(ret.is_truncated);
+        assert_eq!(ret.next_marker.as_deref(), Some("mybucket10"));
+        assert_eq!(ret.max_keys, Some(10));
+        assert_eq!(ret.owner.id, "512**");
+        assert_eq!(ret.buckets.len(), 2);
+        assert_eq!(ret.buckets[0].name, "mybucket01");
+        assert_eq!(ret.buckets[0].region.as_deref(), Some("cn-hangzhou"));
+        assert_eq!(
+            ret.buckets[0].creation_date,
+            "2014-05-14T11:18:32Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(ret.buckets[1].storage_class, StorageClass::IA);
+        assert_eq!(ret.buckets[1].region, None);
+    }
+    #[test]
+    fn empty_list_test() {
+        let xml = r#"<ListAllMyBucketsResult>
+  <Owner><ID>512**</ID><DisplayName>51264</DisplayName></Owner>
+  <Buckets></Buckets>
+</ListAllMyBucketsResult>"#;
+        let ret: ListBucketsResult = quick_xml::de::from_str(xml).unwrap();
+        assert!(!ret.is_truncated);
+        assert!(ret.buckets.is_empty());
+    }
+    #[test]
+    fn bucket_info_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<BucketInfo>
+  <Bucket>
+    <AccessMonitor>Enabled</AccessMonitor>
+    <CreationDate>2013-07-31T10:56:21.000Z</CreationDate>
+    <ExtranetEndpoint>oss-cn-hangzhou.aliyuncs.com</ExtranetEndpoint>
+    <IntranetEndpoint>oss-cn-hangzhou-internal.aliyuncs.com</IntranetEndpoint>
+    <Location>oss-cn-hangzhou</Location>
+    <StorageClass>Standard</StorageClass>
+    <TransferAcceleration>Disabled</TransferAcceleration>
+    <CrossRegionReplication>Disabled</CrossRegionReplication>
+    <Name>oss-example</Name>
+    <ResourceGroupId>rg-aek27tc********</ResourceGroupId>
+    <Owner>
+      <DisplayName>username</DisplayName>
+      <ID>27183473914****</ID>
+    </Owner>
+    <AccessControlList>
+      <Grant>private</Grant>
+    </AccessControlList>
+    <ServerSideEncryptionRule>
+      <SSEAlgorithm>KMS</SSEAlgorithm>
+      <KMSMasterKeyID></KMSMasterKeyID>
+      <KMSDataEncryption>SM4</KMSDataEncryption>
+    </ServerSideEncryptionRule>
+    <BucketPolicy>
+      <LogBucket>examplebucket</LogBucket>
+      <LogPrefix>log/</LogPrefix>
+    </BucketPolicy>
+    <DataRedundancyType>LRS</DataRedundancyType>
+    <Versioning>Enabled</Versioning>
+    <Comment>test</Comment>
+  </Bucket>
+</BucketInfo>"#;
+        let ret = quick_xml::de::from_str::<BucketInfoDocument>(xml)
+            .unwrap()
+            .bucket;
+        assert_eq!(ret.name, "oss-example");
+        assert_eq!(ret.owner.display_name, "username");
+        assert_eq!(ret.acl, Acl::Private);
+        assert_eq!(ret.data_redundancy_type, Some(DataRedundancyType::LRS));
+        assert_eq!(ret.versioning, Some(VersioningStatus::Enabled));
+        assert_eq!(
+            ret.server_side_encryption,
+            Some(ServerSideEncryption::Kms {
+                key_id: None,
+                data_encryption: Some(crate::DataEncryption::Sm4),
+            })
+        );
+        assert_eq!(ret.comment.as_deref(), Some("test"));
+
+        let xml = r#"<BucketInfo><Bucket>
+    <CreationDate>2013-07-31T10:56:21.000Z</CreationDate>
+    <Location>oss-cn-hangzhou</Location>
+    <StorageClass>IA</StorageClass>
+    <Name>oss-example</Name>
+    <Owner><DisplayName>username</DisplayName><ID>27183473914****</ID></Owner>
+    <AccessControlList><Grant>public-read</Grant></AccessControlList>
+    <ServerSideEncryptionRule><SSEAlgorithm>None</SSEAlgorithm></ServerSideEncryptionRule>
+</Bucket></BucketInfo>"#;
+        let ret = quick_xml::de::from_str::<BucketInfoDocument>(xml)
+            .unwrap()
+            .bucket;
+        assert_eq!(ret.storage_class, StorageClass::IA);
+        assert_eq!(ret.versioning, None);
+        assert_eq!(ret.server_side_encryption, None);
+    }
+    #[test]
+    fn location_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<LocationConstraint>oss-cn-hangzhou</LocationConstraint>"#;
+        let ret: LocationConstraint = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(ret.location, "oss-cn-hangzhou");
+    }
+    #[test]
+    fn bucket_stat_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<BucketStat>
+  <Storage>1600</Storage>
+  <ObjectCount>230</ObjectCount>
+  <MultipartUploadCount>40</MultipartUploadCount>
+  <LiveChannelCount>4</LiveChannelCount>
+  <LastModifiedTime>1643341269</LastModifiedTime>
+  <StandardStorage>430</StandardStorage>
+  <StandardObjectCount>66</StandardObjectCount>
+  <InfrequentAccessStorage>2359296</InfrequentAccessStorage>
+  <InfrequentAccessRealStorage>360</InfrequentAccessRealStorage>
+  <InfrequentAccessObjectCount>54</InfrequentAccessObjectCount>
+  <ArchiveStorage>2949120</ArchiveStorage>
+  <ArchiveRealStorage>450</ArchiveRealStorage>
+  <ArchiveObjectCount>74</ArchiveObjectCount>
+</BucketStat>"#;
+        let ret: BucketStat = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(ret.storage, 1600);
+        assert_eq!(ret.multipart_upload_count, 40);
+        assert_eq!(
+            ret.last_modified_time,
+            Some("2022-01-28T03:41:09Z".parse().unwrap())
+        );
+        assert_eq!(ret.usage(StorageClass::IA), (2359296, 54));
+        assert_eq!(ret.usage(StorageClass::ColdArchive), (0, 0));
+    }
+    #[test]
+    fn create_bucket_xml_test() {
+        assert_eq!(
+            create_bucket_xml(Some(StorageClass::IA), Some(DataRedundancyType::ZRS)),
+            "<CreateBucketConfiguration><StorageClass>IA</StorageClass>\
+             <DataRedundancyType>ZRS</DataRedundancyType></CreateBucketConfiguration>"
+        );
+    }
+}
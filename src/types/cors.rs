@@ -0,0 +1,182 @@
+use hyper::{
+    header::{
+        ACCESS_CONTROL_ALLOW_HEADERS, ACCESS_CONTROL_ALLOW_METHODS, ACCESS_CONTROL_ALLOW_ORIGIN,
+        ACCESS_CONTROL_EXPOSE_HEADERS, ACCESS_CONTROL_MAX_AGE,
+    },
+    HeaderMap, Method,
+};
+use serde::{de, Deserialize, Deserializer};
+
+use super::xml::escape;
+
+/// A rule allowing cross-origin requests to a bucket.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct CorsRule {
+    /// Origins allowed, each may contain one `*` wildcard
+    #[serde(rename = "AllowedOrigin", default)]
+    pub allowed_origins: Vec<String>,
+    /// Methods allowed, among GET, PUT, DELETE, POST and HEAD
+    #[serde(rename = "AllowedMethod", default, deserialize_with = "methods")]
+    pub allowed_methods: Vec<Method>,
+    /// Headers a preflight may ask for in Access-Control-Request-Headers, each may contain one
+    /// `*` wildcard
+    #[serde(rename = "AllowedHeader", default)]
+    pub allowed_headers: Vec<String>,
+    /// Response headers the browser exposes to the page
+    #[serde(rename = "ExposeHeader", default)]
+    pub expose_headers: Vec<String>,
+    /// How long in seconds the browser may cache the result of a preflight
+    #[serde(rename = "MaxAgeSeconds")]
+    pub max_age_seconds: Option<u32>,
+}
+
+/// The CORS rules of a bucket, at most 10.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct CorsConfiguration {
+    #[serde(rename = "CORSRule", default)]
+    pub rules: Vec<CorsRule>,
+    /// Whether responses carry `Vary: Origin`, for caches in front of OSS
+    #[serde(rename = "ResponseVary", default)]
+    pub response_vary: bool,
+}
+
+fn methods<'de, D>(deserializer: D) -> Result<Vec<Method>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|method| method.parse().map_err(de::Error::custom))
+        .collect()
+}
+
+impl CorsConfiguration {
+    /// Body of a PutBucketCors request.
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = String::from("<CORSConfiguration>");
+        for rule in &self.rules {
+            xml += "<CORSRule>";
+            for origin in &rule.allowed_origins {
+                xml += &format!("<AllowedOrigin>{}</AllowedOrigin>", escape(origin));
+            }
+            for method in &rule.allowed_methods {
+                xml += &format!("<AllowedMethod>{}</AllowedMethod>", escape(method.as_str()));
+            }
+            for header in &rule.allowed_headers {
+                xml += &format!("<AllowedHeader>{}</AllowedHeader>", escape(header));
+            }
+            for header in &rule.expose_headers {
+                xml += &format!("<ExposeHeader>{}</ExposeHeader>", escape(header));
+            }
+            if let Some(max_age) = rule.max_age_seconds {
+                xml += &format!("<MaxAgeSeconds>{}</MaxAgeSeconds>", max_age);
+            }
+            xml += "</CORSRule>";
+        }
+        xml += &format!("<ResponseVary>{}</ResponseVary>", self.response_vary);
+        xml += "</CORSConfiguration>";
+        xml
+    }
+}
+
+/// What a preflight request was allowed, as returned by `options_object`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CorsPreflight {
+    pub allow_origin: Option<String>,
+    pub allow_methods: Vec<String>,
+    pub allow_headers: Vec<String>,
+    pub expose_headers: Vec<String>,
+    pub max_age_seconds: Option<u32>,
+}
+
+impl CorsPreflight {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| headers.get(name).and_then(|val| val.to_str().ok());
+        let list = |name| {
+            header(name)
+                .map(|val| {
+                    val.split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(str::to_owned)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        CorsPreflight {
+            allow_origin: header(ACCESS_CONTROL_ALLOW_ORIGIN).map(str::to_owned),
+            allow_methods: list(ACCESS_CONTROL_ALLOW_METHODS),
+            allow_headers: list(ACCESS_CONTROL_ALLOW_HEADERS),
+            expose_headers: list(ACCESS_CONTROL_EXPOSE_HEADERS),
+            max_age_seconds: header(ACCESS_CONTROL_MAX_AGE).and_then(|val| val.parse().ok()),
+        }
+    }
+    /// Whether the response allows requests with `method` from `origin`.
+    pub fn allows(&self, origin: &str, method: &Method) -> bool {
+        let origin_allowed =
+            matches!(&self.allow_origin, Some(allowed) if allowed == "*" || allowed == origin);
+        origin_allowed
+            && self
+                .allow_methods
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(method.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cors_configuration_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<CORSConfiguration>
+    <CORSRule>
+      <AllowedOrigin>*</AllowedOrigin>
+      <AllowedMethod>PUT</AllowedMethod>
+      <AllowedMethod>GET</AllowedMethod>
+      <AllowedHeader>Authorization</AllowedHeader>
+    </CORSRule>
+    <CORSRule>
+      <AllowedOrigin>http://example.com</AllowedOrigin>
+      <AllowedOrigin>http://example.net</AllowedOrigin>
+      <AllowedMethod>GET</AllowedMethod>
+      <AllowedHeader>Authorization</AllowedHeader>
+      <ExposeHeader>x-oss-test</ExposeHeader>
+      <ExposeHeader>x-oss-test1</ExposeHeader>
+      <MaxAgeSeconds>100</MaxAgeSeconds>
+    </CORSRule>
+    <ResponseVary>false</ResponseVary>
+</CORSConfiguration>"#;
+        let ret: CorsConfiguration = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(ret.rules.len(), 2);
+        assert_eq!(ret.rules[0].allowed_methods, vec![Method::PUT, Method::GET]);
+        assert_eq!(ret.rules[0].max_age_seconds, None);
+        assert_eq!(ret.rules[1].allowed_origins.len(), 2);
+        assert_eq!(
+            ret.rules[1].expose_headers,
+            vec!["x-oss-test", "x-oss-test1"]
+        );
+        assert_eq!(ret.rules[1].max_age_seconds, Some(100));
+        assert!(!ret.response_vary);
+
+        let parsed: CorsConfiguration = quick_xml::de::from_str(&ret.to_xml()).unwrap();
+        assert_eq!(parsed, ret);
+    }
+    #[test]
+    fn preflight_test() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_ORIGIN,
+            "http://example.com".parse().unwrap(),
+        );
+        headers.insert(ACCESS_CONTROL_ALLOW_METHODS, "GET, PUT".parse().unwrap());
+        headers.insert(ACCESS_CONTROL_MAX_AGE, "100".parse().unwrap());
+        let preflight = CorsPreflight::from_headers(&headers);
+        assert_eq!(preflight.allow_methods, vec!["GET", "PUT"]);
+        assert_eq!(preflight.max_age_seconds, Some(100));
+        assert!(preflight.allows("http://example.com", &Method::PUT));
+        assert!(!preflight.allows("http://example.com", &Method::DELETE));
+        assert!(!preflight.allows("http://example.net", &Method::GET));
+    }
+}
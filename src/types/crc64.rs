@@ -0,0 +1,137 @@
+/// Reversed ECMA-182 polynomial, the variant OSS reports in `x-oss-hash-crc64ecma`.
+const POLY: u64 = 0xC96C_5795_D787_0F42;
+
+const TABLE: [u64; 256] = make_table();
+
+const fn make_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u64;
+        let mut j = 0;
+        while j < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLY
+            } else {
+                crc >> 1
+            };
+            j += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Incremental CRC64-ECMA hasher, compatible with the checksum OSS computes for objects.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Crc64 {
+    value: u64,
+}
+
+impl Crc64 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Checksum of `data` in one go.
+    pub fn checksum(data: &[u8]) -> u64 {
+        let mut crc = Self::new();
+        crc.update(data);
+        crc.value()
+    }
+    pub fn update(&mut self, data: &[u8]) {
+        let mut crc = !self.value;
+        for &byte in data {
+            crc = TABLE[((crc ^ byte as u64) & 0xff) as usize] ^ (crc >> 8);
+        }
+        self.value = !crc;
+    }
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+    /// Checksum of `A + B`, given `crc1 = crc(A)`, `crc2 = crc(B)` and `len2 = len(B)`.
+    ///
+    /// This is how the CRC of a multipart object is derived from the CRCs of its parts.
+    pub fn combine(mut crc1: u64, crc2: u64, mut len2: u64) -> u64 {
+        if len2 == 0 {
+            return crc1;
+        }
+        // Operators appending one, two and four zero bits to a CRC.
+        let mut odd = [0u64; 64];
+        let mut even = [0u64; 64];
+        odd[0] = POLY;
+        let mut row = 1u64;
+        for n in odd.iter_mut().skip(1) {
+            *n = row;
+            row <<= 1;
+        }
+        gf2_matrix_square(&mut even, &odd);
+        gf2_matrix_square(&mut odd, &even);
+        // Apply len2 zero bytes to crc1, squaring the operator once per bit of len2.
+        loop {
+            gf2_matrix_square(&mut even, &odd);
+            if len2 & 1 == 1 {
+                crc1 = gf2_matrix_times(&even, crc1);
+            }
+            len2 >>= 1;
+            if len2 == 0 {
+                break;
+            }
+            gf2_matrix_square(&mut odd, &even);
+            if len2 & 1 == 1 {
+                crc1 = gf2_matrix_times(&odd, crc1);
+            }
+            len2 >>= 1;
+            if len2 == 0 {
+                break;
+            }
+        }
+        crc1 ^ crc2
+    }
+}
+
+fn gf2_matrix_times(mat: &[u64; 64], mut vec: u64) -> u64 {
+    let mut sum = 0;
+    let mut i = 0;
+    while vec != 0 {
+        if vec & 1 == 1 {
+            sum ^= mat[i];
+        }
+        vec >>= 1;
+        i += 1;
+    }
+    sum
+}
+
+fn gf2_matrix_square(square: &mut [u64; 64], mat: &[u64; 64]) {
+    for (n, row) in square.iter_mut().enumerate() {
+        *row = gf2_matrix_times(mat, mat[n]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Crc64;
+
+    #[test]
+    fn checksum_test() {
+        assert_eq!(Crc64::checksum(b""), 0);
+        assert_eq!(Crc64::checksum(b"123456789"), 0x995D_C9BB_DF19_39FA);
+    }
+    #[test]
+    fn update_test() {
+        let mut crc = Crc64::new();
+        crc.update(b"1234");
+        crc.update(b"56789");
+        assert_eq!(crc.value(), Crc64::checksum(b"123456789"));
+    }
+    #[test]
+    fn combine_test() {
+        let data = b"This is just a combine test";
+        for split in 0..=data.len() {
+            let (a, b) = data.split_at(split);
+            let combined = Crc64::combine(Crc64::checksum(a), Crc64::checksum(b), b.len() as u64);
+            assert_eq!(combined, Crc64::checksum(data));
+        }
+    }
+}
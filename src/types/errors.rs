@@ -1,29 +1,58 @@
 use std::{error::Error as StdError, fmt};
 
+use bytes::Bytes;
 use http_client::HttpError;
 
-use hyper::header::{InvalidHeaderName, InvalidHeaderValue};
+use hyper::{
+    header::{InvalidHeaderName, InvalidHeaderValue},
+    HeaderMap, StatusCode,
+};
+use quick_xml::DeError;
+use serde::Deserialize;
 use url::ParseError;
 
 pub(crate) type Result<T> = std::result::Result<T, Error>;
 
-type BoxedError = Box<dyn StdError + Send + Sync>;
+pub(crate) type BoxedError = Box<dyn StdError + Send + Sync>;
 
 /// Error 类型
 pub struct Error {
-    kind: Kind,
+    kind: ErrorKind,
     source: Option<BoxedError>,
 }
-#[derive(Debug)]
-pub(crate) enum Kind {
+/// The category of an `Error`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Client-side encryption failed, or the envelope of an object is unusable
+    Encryption,
+    /// The request could not be sent or the response could not be received
     Http,
-    HeaderToStrError,
+    /// The request or the transfer of a body timed out
+    Timeout,
+    /// The CRC64-ECMA of the transferred data does not match, see `IntegrityError`
+    Integrity,
+    /// An argument was rejected before sending the request
+    InvalidArgument,
+    /// A header could not be built or read
     InvalidHeader,
-    IoError,
-    UrlParsingError,
+    /// Reading or writing local data failed
+    Io,
+    /// A JSON document could not be parsed
+    Json,
+    /// Text such as an access log record could not be parsed
+    Parse,
+    /// A ranged read was not honoured
+    Range,
+    /// OSS returned an error response, see `ServiceError`
+    Service,
+    /// The request URL could not be built
+    Url,
+    /// An XML document could not be parsed
+    Xml,
 }
 impl Error {
-    pub(crate) fn new<E>(kind: Kind, err: E) -> Self
+    pub(crate) fn new<E>(kind: ErrorKind, err: E) -> Self
     where
         E: Into<BoxedError>,
     {
@@ -36,34 +65,157 @@ impl Error {
     where
         E: Into<BoxedError>,
     {
-        Self::new(Kind::HeaderToStrError, err)
+        Self::new(ErrorKind::InvalidHeader, err)
     }
+    pub(crate) fn invalid_argument<E>(err: E) -> Self
+    where
+        E: Into<BoxedError>,
+    {
+        Self::new(ErrorKind::InvalidArgument, err)
+    }
+    pub(crate) fn encryption<E>(err: E) -> Self
+    where
+        E: Into<BoxedError>,
+    {
+        Self::new(ErrorKind::Encryption, err)
+    }
+    pub(crate) fn range<E>(err: E) -> Self
+    where
+        E: Into<BoxedError>,
+    {
+        Self::new(ErrorKind::Range, err)
+    }
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+    /// The error document returned by OSS, if this error is one.
+    pub fn service_error(&self) -> Option<&ServiceError> {
+        match self.kind {
+            ErrorKind::Service => self.source.as_ref()?.downcast_ref(),
+            _ => None,
+        }
+    }
+    /// The bucket, object or upload does not exist.
+    pub fn is_not_found(&self) -> bool {
+        matches!(self.service_error(), Some(err) if err.status == StatusCode::NOT_FOUND)
+    }
+    /// The credentials are not allowed to perform the request.
+    pub fn is_access_denied(&self) -> bool {
+        matches!(self.service_error(), Some(err) if err.status == StatusCode::FORBIDDEN)
+            && !self.is_signature_mismatch()
+    }
+    /// The signature computed by OSS differs from the one sent, usually a wrong secret.
+    pub fn is_signature_mismatch(&self) -> bool {
+        matches!(self.service_error(), Some(err) if err.code == "SignatureDoesNotMatch")
+    }
+    /// OSS rejected the request for exceeding a rate or bandwidth limit.
+    pub fn is_throttled(&self) -> bool {
+        matches!(self.service_error(), Some(err) if err.status == StatusCode::TOO_MANY_REQUESTS
+            || err.code == "SlowDown"
+            || err.code.ends_with("RateLimitExceeded"))
+    }
+    /// The request or the transfer of a body timed out, locally or at OSS.
+    pub fn is_timeout(&self) -> bool {
+        self.kind == ErrorKind::Timeout
+            || matches!(self.service_error(), Some(err) if err.code == "RequestTimeout")
+    }
+    /// Sending the same request again may succeed.
+    ///
+    /// True for timeouts, throttling, connection failures and resets, server errors and a clock
+    /// skew the fresh signature of a new attempt fixes.
+    pub fn is_retryable(&self) -> bool {
+        if self.is_timeout() || self.is_throttled() {
+            return true;
+        }
+        match self.kind {
+            ErrorKind::Http => matches!(
+                self.source.as_ref().and_then(|err| err.downcast_ref::<HttpError>()),
+                Some(err) if err.is_connect() || (err.is_request() && is_connection_reset(err))
+            ),
+            ErrorKind::Service => matches!(
+                self.service_error(),
+                Some(err) if err.status.is_server_error() || err.code == "RequestTimeTooSkewed"
+            ),
+            _ => false,
+        }
+    }
+}
+
+/// Whether an io error in the source chain of `err` shows the connection was dropped midway.
+fn is_connection_reset(err: &(dyn StdError + 'static)) -> bool {
+    use std::io::ErrorKind::*;
+    let mut source = err.source();
+    while let Some(err) = source {
+        if let Some(io) = err.downcast_ref::<std::io::Error>() {
+            if matches!(
+                io.kind(),
+                ConnectionReset | ConnectionAborted | BrokenPipe | UnexpectedEof
+            ) {
+                return true;
+            }
+        }
+        source = err.source();
+    }
+    false
 }
 
 /* From Traits */
 impl From<HttpError> for Error {
     fn from(e: HttpError) -> Error {
-        Error::new(Kind::Http, e)
+        let kind = if e.is_timeout() {
+            ErrorKind::Timeout
+        } else {
+            ErrorKind::Http
+        };
+        Error::new(kind, e)
     }
 }
 impl From<InvalidHeaderName> for Error {
     fn from(e: InvalidHeaderName) -> Error {
-        Error::new(Kind::InvalidHeader, e)
+        Error::new(ErrorKind::InvalidHeader, e)
     }
 }
 impl From<InvalidHeaderValue> for Error {
     fn from(err: InvalidHeaderValue) -> Error {
-        Error::new(Kind::InvalidHeader, err)
+        Error::new(ErrorKind::InvalidHeader, err)
     }
 }
 impl From<ParseError> for Error {
     fn from(err: ParseError) -> Error {
-        Error::new(Kind::UrlParsingError, err)
+        Error::new(ErrorKind::Url, err)
     }
 }
 impl From<std::io::Error> for Error {
     fn from(err: std::io::Error) -> Self {
-        Error::new(Kind::IoError, err)
+        // Integrity failures detected while streaming a body travel as io errors.
+        if matches!(err.get_ref(), Some(inner) if inner.is::<IntegrityError>()) {
+            let inner = err.into_inner().expect("checked above");
+            return Error::new(ErrorKind::Integrity, inner);
+        }
+        if err.kind() == std::io::ErrorKind::TimedOut {
+            return Error::new(ErrorKind::Timeout, err);
+        }
+        Error::new(ErrorKind::Io, err)
+    }
+}
+impl From<DeError> for Error {
+    fn from(err: DeError) -> Self {
+        Error::new(ErrorKind::Xml, err)
+    }
+}
+impl From<serde_json::Error> for Error {
+    fn from(err: serde_json::Error) -> Self {
+        Error::new(ErrorKind::Json, err)
+    }
+}
+impl From<ServiceError> for Error {
+    fn from(err: ServiceError) -> Self {
+        Error::new(ErrorKind::Service, err)
+    }
+}
+impl From<IntegrityError> for Error {
+    fn from(err: IntegrityError) -> Self {
+        Error::new(ErrorKind::Integrity, err)
     }
 }
 
@@ -80,8 +232,19 @@ impl fmt::Debug for Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self.kind {
-            Kind::Http => f.write_str("request or response body error")?,
-            _ => unimplemented!(),
+            ErrorKind::Http => f.write_str("request or response body error")?,
+            ErrorKind::Service => f.write_str("OSS returned an error")?,
+            ErrorKind::Integrity => f.write_str("data integrity check failed")?,
+            ErrorKind::InvalidArgument => f.write_str("invalid argument")?,
+            ErrorKind::Encryption => f.write_str("client-side encryption error")?,
+            ErrorKind::Xml => f.write_str("malformed XML document")?,
+            ErrorKind::Range => f.write_str("range request not honoured")?,
+            ErrorKind::Timeout => f.write_str("operation timed out")?,
+            ErrorKind::InvalidHeader => f.write_str("invalid header")?,
+            ErrorKind::Io => f.write_str("I/O error")?,
+            ErrorKind::Json => f.write_str("malformed JSON document")?,
+            ErrorKind::Parse => f.write_str("parse error")?,
+            ErrorKind::Url => f.write_str("invalid URL")?,
         };
         if let Some(ref e) = self.source {
             write!(f, ": {}", e)?;
@@ -94,3 +257,218 @@ impl std::error::Error for Error {
         self.source.as_ref().map(|e| &**e as _)
     }
 }
+
+/// An error response of OSS, parsed from its `<Error>` document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServiceError {
+    /// Status code of the response
+    pub status: StatusCode,
+    /// Error code, such as `NoSuchKey`, empty when the response carried none
+    pub code: String,
+    pub message: String,
+    pub request_id: Option<String>,
+    pub host_id: Option<String>,
+    /// Detailed error code, such as `0026-00000001`
+    pub ec: Option<String>,
+    /// Link to the documentation of the error
+    pub recommend_doc: Option<String>,
+    /// The undecoded response body, for debugging
+    pub body: Bytes,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "PascalCase", default)]
+struct ErrorDocument {
+    code: String,
+    message: String,
+    request_id: Option<String>,
+    host_id: Option<String>,
+    #[serde(rename = "EC")]
+    ec: Option<String>,
+    recommend_doc: Option<String>,
+}
+
+const OSS_REQUEST_ID: &str = "x-oss-request-id";
+const OSS_ERR: &str = "x-oss-err";
+const OSS_EC: &str = "x-oss-ec";
+
+impl ServiceError {
+    /// Parse the error document in `body`.
+    ///
+    /// Responses to HEAD have no body, the document then comes base64-encoded in `x-oss-err`,
+    /// or only the EC in `x-oss-ec`.
+    pub(crate) fn from_response(status: StatusCode, headers: &HeaderMap, body: Bytes) -> Self {
+        let header = |name| headers.get(name).and_then(|val| val.to_str().ok());
+        let document = if body.is_empty() {
+            header(OSS_ERR).and_then(|val| base64::decode(val).ok())
+        } else {
+            Some(body.to_vec())
+        };
+        let document: ErrorDocument = document
+            .and_then(|doc| quick_xml::de::from_reader(&doc[..]).ok())
+            .unwrap_or_default();
+        ServiceError {
+            status,
+            code: document.code,
+            message: document.message,
+            request_id: document
+                .request_id
+                .or_else(|| header(OSS_REQUEST_ID).map(str::to_owned)),
+            host_id: document.host_id,
+            ec: document.ec.or_else(|| header(OSS_EC).map(str::to_owned)),
+            recommend_doc: document.recommend_doc,
+            body,
+        }
+    }
+}
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.status)?;
+        if !self.code.is_empty() {
+            write!(f, " {}", self.code)?;
+        }
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        if let Some(ref ec) = self.ec {
+            write!(f, " (EC {})", ec)?;
+        }
+        if let Some(ref request_id) = self.request_id {
+            write!(f, " [request id {}]", request_id)?;
+        }
+        Ok(())
+    }
+}
+impl StdError for ServiceError {}
+
+/// The CRC64-ECMA of the transferred data does not match the one reported by OSS.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntegrityError {
+    /// Checksum reported by OSS in `x-oss-hash-crc64ecma`
+    pub expected: u64,
+    /// Checksum computed locally over the transferred bytes
+    pub actual: u64,
+}
+impl fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "crc64ecma mismatch, expected {} but got {}",
+            self.expected, self.actual
+        )
+    }
+}
+impl StdError for IntegrityError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Error>
+  <Code>NoSuchKey</Code>
+  <Message>The specified key does not exist.</Message>
+  <RequestId>5C3D9175B6FC201293AD****</RequestId>
+  <HostId>oss-example.oss-cn-hangzhou.aliyuncs.com</HostId>
+  <Key>example.txt</Key>
+  <EC>0026-00000001</EC>
+  <RecommendDoc>https://api.aliyun.com/troubleshoot?q=0026-00000001</RecommendDoc>
+</Error>"#;
+
+    #[test]
+    fn parse_body_test() {
+        let err = ServiceError::from_response(
+            StatusCode::NOT_FOUND,
+            &HeaderMap::new(),
+            Bytes::from_static(BODY.as_bytes()),
+        );
+        assert_eq!(err.code, "NoSuchKey");
+        assert_eq!(err.message, "The specified key does not exist.");
+        assert_eq!(err.request_id.as_deref(), Some("5C3D9175B6FC201293AD****"));
+        assert_eq!(
+            err.host_id.as_deref(),
+            Some("oss-example.oss-cn-hangzhou.aliyuncs.com")
+        );
+        assert_eq!(err.ec.as_deref(), Some("0026-00000001"));
+        assert_eq!(
+            err.recommend_doc.as_deref(),
+            Some("https://api.aliyun.com/troubleshoot?q=0026-00000001")
+        );
+        assert_eq!(err.body, BODY.as_bytes());
+        let err = Error::from(err);
+        assert_eq!(err.service_error().unwrap().status, StatusCode::NOT_FOUND);
+    }
+    #[test]
+    fn predicates_test() {
+        let service = |status, code: &str| -> Error {
+            ServiceError {
+                status,
+                code: code.to_owned(),
+                message: String::new(),
+                request_id: None,
+                host_id: None,
+                ec: None,
+                recommend_doc: None,
+                body: Bytes::new(),
+            }
+            .into()
+        };
+        let err = service(StatusCode::NOT_FOUND, "NoSuchKey");
+        assert_eq!(err.kind(), ErrorKind::Service);
+        assert!(err.is_not_found() && !err.is_retryable());
+        let err = service(StatusCode::FORBIDDEN, "SignatureDoesNotMatch");
+        assert!(err.is_signature_mismatch() && !err.is_access_denied());
+        assert!(service(StatusCode::FORBIDDEN, "AccessDenied").is_access_denied());
+        let err = service(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "DownloadTrafficRateLimitExceeded",
+        );
+        assert!(err.is_throttled() && err.is_retryable());
+        assert!(service(StatusCode::INTERNAL_SERVER_ERROR, "InternalError").is_retryable());
+        let err = Error::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert!(err.is_timeout() && err.is_retryable());
+        assert!(!Error::invalid_argument("bad").is_retryable());
+        assert_eq!(
+            Error::invalid_argument("bad").to_string(),
+            "invalid argument: bad"
+        );
+    }
+    #[test]
+    fn connection_reset_test() {
+        #[derive(Debug)]
+        struct Wrapped(Box<dyn StdError + Send + Sync>);
+        impl fmt::Display for Wrapped {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "wrapped: {}", self.0)
+            }
+        }
+        impl StdError for Wrapped {
+            fn source(&self) -> Option<&(dyn StdError + 'static)> {
+                Some(&*self.0)
+            }
+        }
+        let reset = std::io::Error::from(std::io::ErrorKind::ConnectionReset);
+        let err = Wrapped(Box::new(Wrapped(Box::new(reset))));
+        assert!(is_connection_reset(&err));
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        assert!(!is_connection_reset(&Wrapped(Box::new(refused))));
+        assert!(!is_connection_reset(&Wrapped(Box::new(
+            Error::invalid_argument("bad")
+        ))));
+    }
+    #[test]
+    fn parse_headers_test() {
+        let mut headers = HeaderMap::new();
+        headers.insert(OSS_ERR, base64::encode(BODY).parse().unwrap());
+        let err = ServiceError::from_response(StatusCode::NOT_FOUND, &headers, Bytes::new());
+        assert_eq!(err.code, "NoSuchKey");
+
+        let mut headers = HeaderMap::new();
+        headers.insert(OSS_EC, "0026-00000001".parse().unwrap());
+        headers.insert(OSS_REQUEST_ID, "5C3D9175B6FC201293AD".parse().unwrap());
+        let err = ServiceError::from_response(StatusCode::NOT_FOUND, &headers, Bytes::new());
+        assert_eq!(err.code, "");
+        assert_eq!(err.ec.as_deref(), Some("0026-00000001"));
+        assert_eq!(err.request_id.as_deref(), Some("5C3D9175B6FC201293AD"));
+    }
+}
@@ -0,0 +1,769 @@
+use std::{collections::HashSet, convert::TryFrom};
+
+use chrono::{DateTime, Duration, Timelike, Utc};
+use serde::Deserialize;
+
+use super::{xml::escape, Error, ObjectSummary, Result, StorageClass};
+
+/// Format of lifecycle dates, midnight UTC.
+const LIFECYCLE_DATE: &str = "%Y-%m-%dT00:00:00.000Z";
+
+/// Most rules a bucket may have.
+const MAX_RULES: usize = 1000;
+
+/// When a lifecycle action applies to an object or upload.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifecycleTime {
+    /// This many days after the object was last modified, or last accessed for transitions
+    /// based on access time
+    Days(u32),
+    /// To objects last modified before this date, which has to be midnight UTC
+    CreatedBefore(DateTime<Utc>),
+}
+
+/// When the current version of an object expires.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LifecycleExpiration {
+    /// This many days after the object was last modified
+    Days(u32),
+    /// On this date, which has to be midnight UTC, for objects modified before it
+    Date(DateTime<Utc>),
+    /// Objects last modified before this date, which has to be midnight UTC
+    CreatedBefore(DateTime<Utc>),
+    /// Remove delete markers that no longer have noncurrent versions behind them, on
+    /// versioned buckets
+    ExpiredObjectDeleteMarker,
+}
+
+/// A change of storage class of current object versions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LifecycleTransition {
+    pub time: LifecycleTime,
+    pub storage_class: StorageClass,
+    /// Count `time` from the last access rather than the last modification, access tracking
+    /// has to be enabled on the bucket
+    pub is_access_time: bool,
+    /// With `is_access_time`, move objects back to Standard when they are accessed
+    pub return_to_std_when_visit: bool,
+}
+
+impl LifecycleTransition {
+    pub fn new(time: LifecycleTime, storage_class: StorageClass) -> Self {
+        Self {
+            time,
+            storage_class,
+            is_access_time: false,
+            return_to_std_when_visit: false,
+        }
+    }
+    /// Count `time` from the last access, moving objects back to Standard on access if
+    /// `return_to_std_when_visit` is set.
+    pub fn with_access_time(mut self, return_to_std_when_visit: bool) -> Self {
+        self.is_access_time = true;
+        self.return_to_std_when_visit = return_to_std_when_visit;
+        self
+    }
+}
+
+/// A change of storage class of noncurrent object versions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NoncurrentVersionTransition {
+    /// Days after the version became noncurrent
+    pub noncurrent_days: u32,
+    pub storage_class: StorageClass,
+    pub is_access_time: bool,
+    pub return_to_std_when_visit: bool,
+}
+
+impl NoncurrentVersionTransition {
+    pub fn new(noncurrent_days: u32, storage_class: StorageClass) -> Self {
+        Self {
+            noncurrent_days,
+            storage_class,
+            is_access_time: false,
+            return_to_std_when_visit: false,
+        }
+    }
+    /// Count from the last access rather than from when the version became noncurrent.
+    pub fn with_access_time(mut self, return_to_std_when_visit: bool) -> Self {
+        self.is_access_time = true;
+        self.return_to_std_when_visit = return_to_std_when_visit;
+        self
+    }
+}
+
+/// A lifecycle rule, applying its actions to the objects matching its prefix and tags.
+///
+/// Built with `LifecycleRule::new` and the `with_*` methods, each adding an action.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LifecycleRule {
+    /// Unique within the bucket
+    pub id: String,
+    /// Objects whose keys start with the prefix, all objects when empty
+    pub prefix: String,
+    /// Objects carrying all of these tags
+    pub tags: Vec<(String, String)>,
+    pub enabled: bool,
+    pub expiration: Option<LifecycleExpiration>,
+    pub transitions: Vec<LifecycleTransition>,
+    /// Abort multipart uploads initiated this long ago, or before this date
+    pub abort_multipart_upload: Option<LifecycleTime>,
+    /// Delete noncurrent versions this many days after they became noncurrent
+    pub noncurrent_version_expiration: Option<u32>,
+    pub noncurrent_version_transitions: Vec<NoncurrentVersionTransition>,
+}
+
+impl LifecycleRule {
+    /// An enabled rule without actions.
+    pub fn new<S1, S2>(id: S1, prefix: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self {
+            id: id.into(),
+            prefix: prefix.into(),
+            tags: vec![],
+            enabled: true,
+            expiration: None,
+            transitions: vec![],
+            abort_multipart_upload: None,
+            noncurrent_version_expiration: None,
+            noncurrent_version_transitions: vec![],
+        }
+    }
+    /// Only apply to objects tagged `key=value`.
+    pub fn with_tag<S1, S2>(mut self, key: S1, value: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        self.tags.push((key.into(), value.into()));
+        self
+    }
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+    pub fn with_expiration(mut self, expiration: LifecycleExpiration) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+    pub fn with_transition(mut self, transition: LifecycleTransition) -> Self {
+        self.transitions.push(transition);
+        self
+    }
+    pub fn with_abort_multipart_upload(mut self, time: LifecycleTime) -> Self {
+        self.abort_multipart_upload = Some(time);
+        self
+    }
+    pub fn with_noncurrent_version_expiration(mut self, noncurrent_days: u32) -> Self {
+        self.noncurrent_version_expiration = Some(noncurrent_days);
+        self
+    }
+    pub fn with_noncurrent_version_transition(
+        mut self,
+        transition: NoncurrentVersionTransition,
+    ) -> Self {
+        self.noncurrent_version_transitions.push(transition);
+        self
+    }
+    fn has_actions(&self) -> bool {
+        self.expiration.is_some()
+            || !self.transitions.is_empty()
+            || self.abort_multipart_upload.is_some()
+            || self.noncurrent_version_expiration.is_some()
+            || !self.noncurrent_version_transitions.is_empty()
+    }
+    fn to_xml(&self) -> String {
+        let mut xml = String::from("<Rule>");
+        xml += &format!("<ID>{}</ID>", escape(&self.id));
+        xml += &format!("<Prefix>{}</Prefix>", escape(&self.prefix));
+        for (key, value) in &self.tags {
+            xml += &format!(
+                "<Tag><Key>{}</Key><Value>{}</Value></Tag>",
+                escape(key),
+                escape(value)
+            );
+        }
+        let status = if self.enabled { "Enabled" } else { "Disabled" };
+        xml += &format!("<Status>{}</Status>", status);
+        if let Some(expiration) = self.expiration {
+            xml += "<Expiration>";
+            xml += &match expiration {
+                LifecycleExpiration::Days(days) => format!("<Days>{}</Days>", days),
+                LifecycleExpiration::Date(date) => {
+                    format!("<Date>{}</Date>", date.format(LIFECYCLE_DATE))
+                }
+                LifecycleExpiration::CreatedBefore(date) => format!(
+                    "<CreatedBeforeDate>{}</CreatedBeforeDate>",
+                    date.format(LIFECYCLE_DATE)
+                ),
+                LifecycleExpiration::ExpiredObjectDeleteMarker => {
+                    "<ExpiredObjectDeleteMarker>true</ExpiredObjectDeleteMarker>".to_owned()
+                }
+            };
+            xml += "</Expiration>";
+        }
+        for transition in &self.transitions {
+            xml += "<Transition>";
+            xml += &time_xml(transition.time);
+            xml += &format!("<StorageClass>{}</StorageClass>", transition.storage_class);
+            if transition.is_access_time {
+                xml += &format!(
+                    "<IsAccessTime>true</IsAccessTime><ReturnToStdWhenVisit>{}</ReturnToStdWhenVisit>",
+                    transition.return_to_std_when_visit
+                );
+            }
+            xml += "</Transition>";
+        }
+        if let Some(time) = self.abort_multipart_upload {
+            xml += &format!(
+                "<AbortMultipartUpload>{}</AbortMultipartUpload>",
+                time_xml(time)
+            );
+        }
+        if let Some(days) = self.noncurrent_version_expiration {
+            xml += &format!(
+                "<NoncurrentVersionExpiration><NoncurrentDays>{}</NoncurrentDays></NoncurrentVersionExpiration>",
+                days
+            );
+        }
+        for transition in &self.noncurrent_version_transitions {
+            xml += "<NoncurrentVersionTransition>";
+            xml += &format!(
+                "<NoncurrentDays>{}</NoncurrentDays><StorageClass>{}</StorageClass>",
+                transition.noncurrent_days, transition.storage_class
+            );
+            if transition.is_access_time {
+                xml += &format!(
+                    "<IsAccessTime>true</IsAccessTime><ReturnToStdWhenVisit>{}</ReturnToStdWhenVisit>",
+                    transition.return_to_std_when_visit
+                );
+            }
+            xml += "</NoncurrentVersionTransition>";
+        }
+        xml += "</Rule>";
+        xml
+    }
+}
+
+fn time_xml(time: LifecycleTime) -> String {
+    match time {
+        LifecycleTime::Days(days) => format!("<Days>{}</Days>", days),
+        LifecycleTime::CreatedBefore(date) => format!(
+            "<CreatedBeforeDate>{}</CreatedBeforeDate>",
+            date.format(LIFECYCLE_DATE)
+        ),
+    }
+}
+
+fn is_midnight(date: &DateTime<Utc>) -> bool {
+    date.num_seconds_from_midnight() == 0 && date.nanosecond() == 0
+}
+
+/// The lifecycle rules of a bucket.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct LifecycleConfiguration {
+    #[serde(rename = "Rule", default)]
+    pub rules: Vec<LifecycleRule>,
+}
+
+impl LifecycleConfiguration {
+    pub fn new(rules: Vec<LifecycleRule>) -> Self {
+        Self { rules }
+    }
+    /// Check what OSS would reject: duplicate IDs, rules without actions and dates that are
+    /// not midnight UTC.
+    pub(crate) fn validate(&self) -> Result<()> {
+        if self.rules.is_empty() || self.rules.len() > MAX_RULES {
+            return Err(Error::invalid_argument(format!(
+                "a lifecycle configuration takes 1 to {} rules",
+                MAX_RULES
+            )));
+        }
+        let mut ids = HashSet::new();
+        for rule in &self.rules {
+            if !ids.insert(rule.id.as_str()) {
+                return Err(Error::invalid_argument(format!(
+                    "duplicate lifecycle rule ID {:?}",
+                    rule.id
+                )));
+            }
+            if !rule.has_actions() {
+                return Err(Error::invalid_argument(format!(
+                    "lifecycle rule {:?} has no actions",
+                    rule.id
+                )));
+            }
+            let mut dates = vec![];
+            match rule.expiration {
+                Some(LifecycleExpiration::Date(date))
+                | Some(LifecycleExpiration::CreatedBefore(date)) => dates.push(date),
+                _ => {}
+            }
+            for transition in &rule.transitions {
+                if let LifecycleTime::CreatedBefore(date) = transition.time {
+                    dates.push(date);
+                }
+            }
+            if let Some(LifecycleTime::CreatedBefore(date)) = rule.abort_multipart_upload {
+                dates.push(date);
+            }
+            if !dates.iter().all(is_midnight) {
+                return Err(Error::invalid_argument(format!(
+                    "lifecycle rule {:?} has a date that is not midnight UTC",
+                    rule.id
+                )));
+            }
+        }
+        Ok(())
+    }
+    /// Body of a PutBucketLifecycle request.
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = String::from("<LifecycleConfiguration>");
+        for rule in &self.rules {
+            xml += &rule.to_xml();
+        }
+        xml += "</LifecycleConfiguration>";
+        xml
+    }
+}
+
+/// What the lifecycle rules of a bucket do to an object, as found by
+/// `LifecycleConfiguration::evaluate`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LifecycleAction {
+    /// No rule applies yet
+    Keep,
+    /// The object is deleted by rule `rule_id`
+    Expire { rule_id: String },
+    /// The object is moved to `storage_class` by rule `rule_id`
+    Transition {
+        rule_id: String,
+        storage_class: StorageClass,
+    },
+}
+
+impl LifecycleConfiguration {
+    /// The action the rules take on each of `objects` at `now`, in order.
+    ///
+    /// Only enabled rules matching the key prefix and all tags of a rule count, so objects
+    /// have to carry their tags for tag filters to match. Expiration wins over transitions, and
+    /// of the transitions due the one to the coldest storage class wins, provided it is colder
+    /// than the current class. Transitions based on access time are counted from the last
+    /// modification, the earliest the last access can be, so they may be reported early.
+    /// Actions on noncurrent versions, delete markers and multipart uploads are left out.
+    pub fn evaluate<'a>(
+        &self,
+        objects: &'a [ObjectSummary],
+        now: DateTime<Utc>,
+    ) -> Vec<(&'a ObjectSummary, LifecycleAction)> {
+        objects
+            .iter()
+            .map(|object| (object, self.evaluate_object(object, now)))
+            .collect()
+    }
+    /// The action the rules take on `object` at `now`.
+    pub fn evaluate_object(&self, object: &ObjectSummary, now: DateTime<Utc>) -> LifecycleAction {
+        let rules = self.rules.iter().filter(|rule| rule.matches(object));
+        let mut transition: Option<(&str, StorageClass)> = None;
+        for rule in rules {
+            if matches!(rule.expiration, Some(exp) if exp.is_due(object.last_modified, now)) {
+                return LifecycleAction::Expire {
+                    rule_id: rule.id.clone(),
+                };
+            }
+            for due in rule
+                .transitions
+                .iter()
+                .filter(|tr| tr.time.is_due(object.last_modified, now))
+            {
+                let colder = match transition {
+                    Some((_, class)) => coldness(due.storage_class) > coldness(class),
+                    None => coldness(due.storage_class) > coldness(object.storage_class),
+                };
+                if colder {
+                    transition = Some((&rule.id, due.storage_class));
+                }
+            }
+        }
+        match transition {
+            Some((rule_id, storage_class)) => LifecycleAction::Transition {
+                rule_id: rule_id.to_owned(),
+                storage_class,
+            },
+            None => LifecycleAction::Keep,
+        }
+    }
+}
+
+impl LifecycleRule {
+    /// Whether the rule is enabled and applies to `object`.
+    fn matches(&self, object: &ObjectSummary) -> bool {
+        self.enabled
+            && object.key.starts_with(&self.prefix)
+            && self.tags.iter().all(|tag| object.tags.contains(tag))
+    }
+}
+
+impl LifecycleTime {
+    fn is_due(self, last_modified: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        match self {
+            LifecycleTime::Days(days) => now >= last_modified + Duration::days(days.into()),
+            LifecycleTime::CreatedBefore(date) => last_modified < date,
+        }
+    }
+}
+
+impl LifecycleExpiration {
+    fn is_due(self, last_modified: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        match self {
+            LifecycleExpiration::Days(days) => LifecycleTime::Days(days).is_due(last_modified, now),
+            LifecycleExpiration::Date(date) => now >= date && last_modified < date,
+            LifecycleExpiration::CreatedBefore(date) => last_modified < date,
+            LifecycleExpiration::ExpiredObjectDeleteMarker => false,
+        }
+    }
+}
+
+/// Rank of storage classes from the warmest, transitions only move objects to colder ones.
+fn coldness(storage_class: StorageClass) -> u8 {
+    match storage_class {
+        StorageClass::Standard => 0,
+        StorageClass::IA => 1,
+        StorageClass::Archive => 2,
+        StorageClass::ColdArchive => 3,
+        StorageClass::DeepColdArchive => 4,
+    }
+}
+
+/// The `<Rule>` element as sent by OSS, before the actions are sorted out.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RuleDocument {
+    #[serde(rename = "ID", default)]
+    id: String,
+    #[serde(default)]
+    prefix: String,
+    #[serde(default)]
+    tag: Vec<TagDocument>,
+    status: String,
+    expiration: Option<TimeDocument>,
+    #[serde(default)]
+    transition: Vec<TimeDocument>,
+    abort_multipart_upload: Option<TimeDocument>,
+    noncurrent_version_expiration: Option<TimeDocument>,
+    #[serde(default)]
+    noncurrent_version_transition: Vec<TimeDocument>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TagDocument {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+/// The elements of the actions of a rule, each using a few of them.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TimeDocument {
+    days: Option<u32>,
+    date: Option<DateTime<Utc>>,
+    created_before_date: Option<DateTime<Utc>>,
+    expired_object_delete_marker: Option<bool>,
+    noncurrent_days: Option<u32>,
+    storage_class: Option<StorageClass>,
+    #[serde(default)]
+    is_access_time: bool,
+    #[serde(default)]
+    return_to_std_when_visit: bool,
+}
+
+impl TimeDocument {
+    fn time(&self) -> std::result::Result<LifecycleTime, String> {
+        match (self.days, self.created_before_date) {
+            (Some(days), _) => Ok(LifecycleTime::Days(days)),
+            (None, Some(date)) => Ok(LifecycleTime::CreatedBefore(date)),
+            (None, None) => Err("missing Days or CreatedBeforeDate".to_owned()),
+        }
+    }
+    fn storage_class(&self) -> std::result::Result<StorageClass, String> {
+        self.storage_class
+            .ok_or_else(|| "missing StorageClass".to_owned())
+    }
+    fn noncurrent_days(&self) -> std::result::Result<u32, String> {
+        self.noncurrent_days
+            .ok_or_else(|| "missing NoncurrentDays".to_owned())
+    }
+}
+
+impl TryFrom<RuleDocument> for LifecycleRule {
+    type Error = String;
+
+    fn try_from(doc: RuleDocument) -> std::result::Result<Self, Self::Error> {
+        let expiration = match doc.expiration {
+            Some(exp) => Some(match exp {
+                TimeDocument {
+                    days: Some(days), ..
+                } => LifecycleExpiration::Days(days),
+                TimeDocument {
+                    date: Some(date), ..
+                } => LifecycleExpiration::Date(date),
+                TimeDocument {
+                    created_before_date: Some(date),
+                    ..
+                } => LifecycleExpiration::CreatedBefore(date),
+                TimeDocument {
+                    expired_object_delete_marker: Some(true),
+                    ..
+                } => LifecycleExpiration::ExpiredObjectDeleteMarker,
+                _ => return Err("unknown lifecycle expiration".to_owned()),
+            }),
+            None => None,
+        };
+        let transitions = doc
+            .transition
+            .iter()
+            .map(|doc| {
+                Ok(LifecycleTransition {
+                    time: doc.time()?,
+                    storage_class: doc.storage_class()?,
+                    is_access_time: doc.is_access_time,
+                    return_to_std_when_visit: doc.return_to_std_when_visit,
+                })
+            })
+            .collect::<std::result::Result<_, String>>()?;
+        let noncurrent_version_transitions = doc
+            .noncurrent_version_transition
+            .iter()
+            .map(|doc| {
+                Ok(NoncurrentVersionTransition {
+                    noncurrent_days: doc.noncurrent_days()?,
+                    storage_class: doc.storage_class()?,
+                    is_access_time: doc.is_access_time,
+                    return_to_std_when_visit: doc.return_to_std_when_visit,
+                })
+            })
+            .collect::<std::result::Result<_, String>>()?;
+        Ok(LifecycleRule {
+            id: doc.id,
+            prefix: doc.prefix,
+            tags: doc
+                .tag
+                .into_iter()
+                .map(|tag| (tag.key, tag.value))
+                .collect(),
+            enabled: doc.status == "Enabled",
+            expiration,
+            transitions,
+            abort_multipart_upload: doc
+                .abort_multipart_upload
+                .as_ref()
+                .map(TimeDocument::time)
+                .transpose()?,
+            noncurrent_version_expiration: doc
+                .noncurrent_version_expiration
+                .as_ref()
+                .map(TimeDocument::noncurrent_days)
+                .transpose()?,
+            noncurrent_version_transitions,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for LifecycleRule {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Self::try_from(RuleDocument::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lifecycle_configuration_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<LifecycleConfiguration>
+  <Rule>
+    <ID>delete after one day</ID>
+    <Prefix>logs1/</Prefix>
+    <Status>Enabled</Status>
+    <Expiration>
+      <Days>1</Days>
+    </Expiration>
+  </Rule>
+  <Rule>
+    <ID>mtime transition1</ID>
+    <Prefix>logs2/</Prefix>
+    <Tag><Key>key1</Key><Value>value1</Value></Tag>
+    <Tag><Key>key2</Key><Value>value2</Value></Tag>
+    <Status>Disabled</Status>
+    <Transition>
+      <Days>30</Days>
+      <StorageClass>IA</StorageClass>
+      <IsAccessTime>true</IsAccessTime>
+      <ReturnToStdWhenVisit>false</ReturnToStdWhenVisit>
+    </Transition>
+    <Transition>
+      <CreatedBeforeDate>2022-10-12T00:00:00.000Z</CreatedBeforeDate>
+      <StorageClass>Archive</StorageClass>
+    </Transition>
+    <AbortMultipartUpload>
+      <Days>3</Days>
+    </AbortMultipartUpload>
+  </Rule>
+  <Rule>
+    <ID>versions</ID>
+    <Prefix></Prefix>
+    <Status>Enabled</Status>
+    <Expiration>
+      <ExpiredObjectDeleteMarker>true</ExpiredObjectDeleteMarker>
+    </Expiration>
+    <NoncurrentVersionExpiration>
+      <NoncurrentDays>5</NoncurrentDays>
+    </NoncurrentVersionExpiration>
+    <NoncurrentVersionTransition>
+      <NoncurrentDays>2</NoncurrentDays>
+      <StorageClass>ColdArchive</StorageClass>
+    </NoncurrentVersionTransition>
+  </Rule>
+</LifecycleConfiguration>"#;
+        let ret: LifecycleConfiguration = quick_xml::de::from_str(xml).unwrap();
+        let date: DateTime<Utc> = "2022-10-12T00:00:00Z".parse().unwrap();
+        let expected = LifecycleConfiguration::new(vec![
+            LifecycleRule::new("delete after one day", "logs1/")
+                .with_expiration(LifecycleExpiration::Days(1)),
+            LifecycleRule::new("mtime transition1", "logs2/")
+                .with_tag("key1", "value1")
+                .with_tag("key2", "value2")
+                .with_enabled(false)
+                .with_transition(
+                    LifecycleTransition::new(LifecycleTime::Days(30), StorageClass::IA)
+                        .with_access_time(false),
+                )
+                .with_transition(LifecycleTransition::new(
+                    LifecycleTime::CreatedBefore(date),
+                    StorageClass::Archive,
+                ))
+                .with_abort_multipart_upload(LifecycleTime::Days(3)),
+            LifecycleRule::new("versions", "")
+                .with_expiration(LifecycleExpiration::ExpiredObjectDeleteMarker)
+                .with_noncurrent_version_expiration(5)
+                .with_noncurrent_version_transition(NoncurrentVersionTransition::new(
+                    2,
+                    StorageClass::ColdArchive,
+                )),
+        ]);
+        assert_eq!(ret, expected);
+
+        let parsed: LifecycleConfiguration = quick_xml::de::from_str(&ret.to_xml()).unwrap();
+        assert_eq!(parsed, expected);
+        assert!(expected.validate().is_ok());
+    }
+    #[test]
+    fn evaluate_test() {
+        let object = |key: &str, last_modified: &str, storage_class| ObjectSummary {
+            key: key.to_owned(),
+            last_modified: last_modified.parse().unwrap(),
+            etag: crate::ETag::new("abc"),
+            size: 1,
+            storage_class,
+            object_type: None,
+            tags: vec![],
+        };
+        let mut tagged = object(
+            "logs/tagged",
+            "2022-01-01T08:00:00Z",
+            StorageClass::Standard,
+        );
+        tagged.tags.push(("keep".to_owned(), "no".to_owned()));
+        let objects = vec![
+            object("logs/new", "2022-03-20T08:00:00Z", StorageClass::Standard),
+            object("logs/month", "2022-02-20T08:00:00Z", StorageClass::Standard),
+            object(
+                "logs/quarter",
+                "2021-12-01T08:00:00Z",
+                StorageClass::Standard,
+            ),
+            object(
+                "logs/archived",
+                "2022-02-01T08:00:00Z",
+                StorageClass::Archive,
+            ),
+            object("data/old", "2020-01-01T08:00:00Z", StorageClass::Standard),
+            tagged,
+        ];
+        let lifecycle = LifecycleConfiguration::new(vec![
+            LifecycleRule::new("logs", "logs/")
+                .with_transition(LifecycleTransition::new(
+                    LifecycleTime::Days(30),
+                    StorageClass::IA,
+                ))
+                .with_transition(LifecycleTransition::new(
+                    LifecycleTime::Days(60),
+                    StorageClass::Archive,
+                ))
+                .with_expiration(LifecycleExpiration::Days(180)),
+            LifecycleRule::new("tagged", "")
+                .with_tag("keep", "no")
+                .with_expiration(LifecycleExpiration::Days(1)),
+            LifecycleRule::new("disabled", "data/")
+                .with_enabled(false)
+                .with_expiration(LifecycleExpiration::Days(1)),
+        ]);
+        let now = "2022-04-01T00:00:00Z".parse().unwrap();
+        let actions: Vec<_> = lifecycle
+            .evaluate(&objects, now)
+            .into_iter()
+            .map(|(_, action)| action)
+            .collect();
+        let transition = |storage_class| LifecycleAction::Transition {
+            rule_id: "logs".to_owned(),
+            storage_class,
+        };
+        assert_eq!(
+            actions,
+            vec![
+                LifecycleAction::Keep,
+                transition(StorageClass::IA),
+                transition(StorageClass::Archive),
+                LifecycleAction::Keep,
+                LifecycleAction::Keep,
+                LifecycleAction::Expire {
+                    rule_id: "tagged".to_owned()
+                },
+            ]
+        );
+        let later = "2022-08-01T00:00:00Z".parse().unwrap();
+        assert_eq!(
+            lifecycle.evaluate_object(&objects[2], later),
+            LifecycleAction::Expire {
+                rule_id: "logs".to_owned()
+            }
+        );
+    }
+    #[test]
+    fn validate_test() {
+        let rule = LifecycleRule::new("rule", "tmp/");
+        assert!(LifecycleConfiguration::new(vec![]).validate().is_err());
+        assert!(LifecycleConfiguration::new(vec![rule.clone()])
+            .validate()
+            .is_err());
+        let rule = rule.with_expiration(LifecycleExpiration::Days(1));
+        assert!(
+            LifecycleConfiguration::new(vec![rule.clone(), rule.clone()])
+                .validate()
+                .is_err()
+        );
+        let date = "2022-10-12T08:00:00Z".parse().unwrap();
+        let rule = rule.with_expiration(LifecycleExpiration::CreatedBefore(date));
+        assert!(LifecycleConfiguration::new(vec![rule]).validate().is_err());
+    }
+}
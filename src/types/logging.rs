@@ -0,0 +1,301 @@
+use std::{
+    net::IpAddr,
+    pin::Pin,
+    str::FromStr,
+    task::{Context, Poll},
+};
+
+use bytes::BytesMut;
+use chrono::{DateTime, FixedOffset};
+use futures::{ready, Stream};
+use pin_project::pin_project;
+use serde::Deserialize;
+
+use super::{xml::escape, ByteStream, Error, ErrorKind, Result, StorageClass};
+
+/// Format of the time of access log records, such as `02/May/2012:00:00:04 +0800`.
+const LOG_TIME: &str = "%d/%b/%Y:%H:%M:%S %z";
+
+/// Where the access logs of a bucket are written.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct BucketLogging {
+    /// The bucket the logs are stored in, owned by the same account and in the same region
+    pub target_bucket: String,
+    /// Prefix of the log objects, they are named `<prefix><bucket><time>`
+    #[serde(default)]
+    pub target_prefix: String,
+}
+
+impl BucketLogging {
+    pub fn new<S1, S2>(target_bucket: S1, target_prefix: S2) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        Self {
+            target_bucket: target_bucket.into(),
+            target_prefix: target_prefix.into(),
+        }
+    }
+    /// Body of a PutBucketLogging request.
+    pub(crate) fn to_xml(&self) -> String {
+        format!(
+            "<BucketLoggingStatus><LoggingEnabled><TargetBucket>{}</TargetBucket>\
+             <TargetPrefix>{}</TargetPrefix></LoggingEnabled></BucketLoggingStatus>",
+            escape(&self.target_bucket),
+            escape(&self.target_prefix)
+        )
+    }
+}
+
+/// The document `get_bucket_logging` parses, without `LoggingEnabled` when logging is off.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct BucketLoggingStatus {
+    pub(crate) logging_enabled: Option<BucketLogging>,
+}
+
+/// A line of the access logs OSS writes for a bucket.
+///
+/// Fields logged as `-` are `None`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccessLogRecord {
+    pub remote_ip: Option<IpAddr>,
+    pub time: DateTime<FixedOffset>,
+    /// The request line, such as `GET /aliyun-logo.png HTTP/1.1`
+    pub request_uri: String,
+    pub http_status: u16,
+    /// Bytes sent to the client
+    pub sent_bytes: Option<u64>,
+    /// Time taken by the request in milliseconds
+    pub request_time: Option<u64>,
+    pub referer: Option<String>,
+    pub user_agent: Option<String>,
+    pub host: Option<String>,
+    pub request_id: Option<String>,
+    /// Whether the request was logged by the logging feature
+    pub logging_flag: Option<bool>,
+    pub requester_aliyun_id: Option<String>,
+    /// The API called, such as `GetObject`
+    pub operation: Option<String>,
+    pub bucket: Option<String>,
+    /// The URL-encoded object key
+    pub key: Option<String>,
+    pub object_size: Option<u64>,
+    /// Time OSS spent on the request in milliseconds
+    pub server_cost_time: Option<u64>,
+    pub error_code: Option<String>,
+    /// Length of the request in bytes
+    pub request_length: Option<u64>,
+    pub user_id: Option<String>,
+    pub delta_data_size: Option<i64>,
+    pub sync_request: Option<String>,
+    pub storage_class: Option<StorageClass>,
+    pub target_storage_class: Option<StorageClass>,
+    pub access_key_id: Option<String>,
+}
+
+/// Split a log line into its fields, keeping `"quoted"` and `[bracketed]` fields whole.
+fn fields(line: &str) -> std::result::Result<Vec<&str>, String> {
+    let mut fields = vec![];
+    let mut rest = line.trim();
+    while !rest.is_empty() {
+        let (field, next) = match rest.as_bytes()[0] {
+            b'"' => {
+                let end = rest[1..]
+                    .find('"')
+                    .ok_or_else(|| "unterminated quoted field".to_owned())?;
+                (&rest[1..end + 1], &rest[end + 2..])
+            }
+            b'[' => {
+                let end = rest
+                    .find(']')
+                    .ok_or_else(|| "unterminated bracketed field".to_owned())?;
+                (&rest[1..end], &rest[end + 1..])
+            }
+            _ => match rest.find(' ') {
+                Some(end) => (&rest[..end], &rest[end..]),
+                None => (rest, ""),
+            },
+        };
+        fields.push(field);
+        rest = next.trim_start();
+    }
+    Ok(fields)
+}
+
+impl FromStr for AccessLogRecord {
+    type Err = String;
+
+    fn from_str(line: &str) -> std::result::Result<Self, Self::Err> {
+        let fields = fields(line)?;
+        if fields.len() < 7 {
+            return Err(format!(
+                "expected at least 7 fields, found {}",
+                fields.len()
+            ));
+        }
+        // Fields beyond the end of older, shorter records are missing rather than `-`.
+        let text = |idx: usize| {
+            fields
+                .get(idx)
+                .filter(|field| !field.is_empty() && **field != "-")
+                .map(|field| (*field).to_owned())
+        };
+        let number = |idx: usize| text(idx).and_then(|field| field.parse().ok());
+        Ok(AccessLogRecord {
+            remote_ip: text(0).and_then(|field| field.parse().ok()),
+            time: DateTime::parse_from_str(fields[3], LOG_TIME)
+                .map_err(|err| format!("invalid time {:?}: {}", fields[3], err))?,
+            request_uri: fields[4].to_owned(),
+            http_status: fields[5]
+                .parse()
+                .map_err(|_| format!("invalid status {:?}", fields[5]))?,
+            sent_bytes: number(6),
+            request_time: number(7),
+            referer: text(8),
+            user_agent: text(9),
+            host: text(10),
+            request_id: text(11),
+            logging_flag: text(12).and_then(|field| field.parse().ok()),
+            requester_aliyun_id: text(13),
+            operation: text(14),
+            bucket: text(15),
+            key: text(16),
+            object_size: number(17),
+            server_cost_time: number(18),
+            error_code: text(19),
+            request_length: number(20),
+            user_id: text(21),
+            delta_data_size: text(22).and_then(|field| field.parse().ok()),
+            sync_request: text(23),
+            storage_class: text(24).and_then(|field| field.parse().ok()),
+            target_storage_class: text(25).and_then(|field| field.parse().ok()),
+            access_key_id: text(27),
+        })
+    }
+}
+
+/// Parses the access log lines of a `ByteStream`, such as the body of a log object, into
+/// `AccessLogRecord`s. Blank lines are skipped.
+#[pin_project]
+pub struct AccessLogStream {
+    #[pin]
+    inner: ByteStream,
+    /// Bytes read past the last complete line
+    buf: BytesMut,
+    finished: bool,
+}
+
+impl AccessLogStream {
+    pub fn new(inner: ByteStream) -> Self {
+        Self {
+            inner,
+            buf: BytesMut::new(),
+            finished: false,
+        }
+    }
+}
+
+fn parse_line(line: &[u8]) -> Option<Result<AccessLogRecord>> {
+    let line = match std::str::from_utf8(line) {
+        Ok(line) => line.trim(),
+        Err(err) => return Some(Err(Error::new(ErrorKind::Parse, err))),
+    };
+    if line.is_empty() {
+        return None;
+    }
+    Some(
+        line.parse()
+            .map_err(|err| Error::new(ErrorKind::Parse, format!("{}: {:?}", err, line))),
+    )
+}
+
+impl Stream for AccessLogStream {
+    type Item = Result<AccessLogRecord>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            while let Some(pos) = this.buf.iter().position(|byte| *byte == b'\n') {
+                let line = this.buf.split_to(pos + 1);
+                if let Some(record) = parse_line(&line) {
+                    return Poll::Ready(Some(record));
+                }
+            }
+            if *this.finished {
+                let line = this.buf.split();
+                return Poll::Ready(parse_line(&line));
+            }
+            match ready!(this.inner.as_mut().poll_next(cx)) {
+                Some(Ok(chunk)) => this.buf.extend_from_slice(&chunk),
+                Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
+                None => *this.finished = true,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use futures::{stream, TryStreamExt};
+
+    const LINE: &str = r#"1.2.3.4 - - [02/May/2012:00:00:04 +0800] "GET /aliyun-logo.png HTTP/1.1" 200 5576 444 "http://www.aliyun.com/product/oss" "curl/7.15.5" "test-bucket.oss-cn-hangzhou.aliyuncs.com" "24024C4A82C8D37CE5F9****" "true" "113422" "GetObject" "test-bucket" "aliyun-logo.png" 5576 83 "-" 200 "-" 0 "-" "Standard" "-" "-" "LTAI****""#;
+
+    #[test]
+    fn bucket_logging_test() {
+        let logging = BucketLogging::new("examplebucket", "MyLog-");
+        let ret: BucketLoggingStatus = quick_xml::de::from_str(&logging.to_xml()).unwrap();
+        assert_eq!(ret.logging_enabled, Some(logging));
+        let ret: BucketLoggingStatus =
+            quick_xml::de::from_str("<BucketLoggingStatus></BucketLoggingStatus>").unwrap();
+        assert_eq!(ret.logging_enabled, None);
+    }
+    #[test]
+    fn access_log_record_test() {
+        let record: AccessLogRecord = LINE.parse().unwrap();
+        assert_eq!(record.remote_ip, Some("1.2.3.4".parse().unwrap()));
+        assert_eq!(
+            record.time,
+            DateTime::parse_from_rfc3339("2012-05-02T00:00:04+08:00").unwrap()
+        );
+        assert_eq!(record.request_uri, "GET /aliyun-logo.png HTTP/1.1");
+        assert_eq!(record.http_status, 200);
+        assert_eq!(record.sent_bytes, Some(5576));
+        assert_eq!(record.request_time, Some(444));
+        assert_eq!(record.user_agent.as_deref(), Some("curl/7.15.5"));
+        assert_eq!(record.logging_flag, Some(true));
+        assert_eq!(record.operation.as_deref(), Some("GetObject"));
+        assert_eq!(record.key.as_deref(), Some("aliyun-logo.png"));
+        assert_eq!(record.error_code, None);
+        assert_eq!(record.delta_data_size, Some(0));
+        assert_eq!(record.storage_class, Some(StorageClass::Standard));
+        assert_eq!(record.access_key_id.as_deref(), Some("LTAI****"));
+
+        assert!("1.2.3.4 - - [02/May/2012"
+            .parse::<AccessLogRecord>()
+            .is_err());
+    }
+    #[tokio::test]
+    async fn access_log_stream_test() {
+        let log = format!("{}\n\n{}\ngarbage", LINE, LINE);
+        // Chunks split lines at arbitrary points.
+        let chunks: Vec<_> = log
+            .as_bytes()
+            .chunks(7)
+            .map(|chunk| Ok(Bytes::copy_from_slice(chunk)))
+            .collect();
+        let records = AccessLogStream::new(ByteStream::new(stream::iter(chunks)));
+        let ret: Vec<_> = futures::StreamExt::collect(records).await;
+        assert_eq!(ret.len(), 3);
+        assert!(ret[0].is_ok() && ret[1].is_ok());
+        assert_eq!(ret[2].as_ref().unwrap_err().kind(), ErrorKind::Parse);
+
+        let chunks = vec![Ok(Bytes::from(LINE))];
+        let records = AccessLogStream::new(ByteStream::new(stream::iter(chunks)));
+        assert_eq!(records.try_collect::<Vec<_>>().await.unwrap().len(), 1);
+    }
+}
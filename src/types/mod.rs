@@ -1,19 +1,70 @@
+mod acl;
+mod bucket;
+mod cors;
+mod crc64;
 mod errors;
+mod lifecycle;
+mod logging;
+mod multipart;
+mod object_metadata;
+mod object_summary;
 mod options;
 mod payload;
+mod policy;
+mod preconditions;
+mod progress;
+mod range;
+mod referer;
 mod regions;
 mod request;
 mod response;
+mod retry;
 mod schema;
+mod server_side_encryption;
 mod stream;
+mod throttle;
+mod website;
+mod xml;
 
+pub use acl::{AccessControlPolicy, Acl};
+pub use bucket::{
+    BucketInfo, BucketStat, BucketSummary, DataRedundancyType, ListBucketsResult, Owner,
+    VersioningStatus,
+};
+pub use cors::{CorsConfiguration, CorsPreflight, CorsRule};
+pub use crc64::Crc64;
+pub use errors::{Error, ErrorKind, IntegrityError, ServiceError};
+pub use lifecycle::{
+    LifecycleAction, LifecycleConfiguration, LifecycleExpiration, LifecycleRule, LifecycleTime,
+    LifecycleTransition, NoncurrentVersionTransition,
+};
+pub use logging::{AccessLogRecord, AccessLogStream, BucketLogging};
+pub use multipart::{CompletedPart, InitiateMultipartUploadResult};
+pub use object_metadata::{ObjectMetadata, ObjectType, StorageClass};
+pub use object_summary::ObjectSummary;
 pub use options::*;
-pub use payload::Payload;
+pub use payload::{Payload, PayloadSource};
+pub use policy::{Conditions, Effect, PolicyDocument, Statement};
+pub use preconditions::{Conditional, ETag, Preconditions};
+pub use progress::{Progress, ProgressEvent, ProgressListener};
+pub use range::{ByteRange, ContentRange};
+pub use referer::RefererConfiguration;
 pub use regions::Region;
 pub use request::Metas;
 pub use response::Response;
+pub use retry::RetryPolicy;
 pub use schema::Schema;
+pub use server_side_encryption::{DataEncryption, ServerSideEncryption};
 pub use stream::ByteStream;
+pub use throttle::{RateLimiter, TrafficLimit, TrafficLimitError};
+pub use website::{
+    ErrorDocument, ExternalRedirect, IndexDocument, KeyRewrite, MirrorHeaders, MirrorRedirect,
+    Redirect, RoutingCondition, RoutingRule, SubDirType, WebsiteConfiguration,
+};
 
-pub(crate) use errors::{Error, Result};
+pub(crate) use acl::{OSS_ACL, OSS_OBJECT_ACL};
+pub(crate) use bucket::{create_bucket_xml, BucketInfoDocument, LocationConstraint};
+pub(crate) use errors::{BoxedError, Result};
+pub(crate) use logging::BucketLoggingStatus;
+pub(crate) use multipart::complete_multipart_upload_xml;
 pub(crate) use request::Request;
@@ -0,0 +1,70 @@
+use serde::Deserialize;
+
+use super::xml::escape;
+
+/// Result of `initiate_multipart_upload`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct InitiateMultipartUploadResult {
+    /// The bucket the upload belongs to
+    pub bucket: String,
+    /// The name of the object being uploaded
+    pub key: String,
+    /// The ID identifying the upload in subsequent part requests
+    pub upload_id: String,
+}
+
+/// A part passed to `complete_multipart_upload`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompletedPart {
+    /// Number of the part, from 1 to 10000
+    pub part_number: u32,
+    /// ETag returned when the part was uploaded
+    pub e_tag: String,
+}
+
+/// Body of a CompleteMultipartUpload request.
+pub(crate) fn complete_multipart_upload_xml(parts: &[CompletedPart]) -> String {
+    let mut xml = String::from("<CompleteMultipartUpload>");
+    for part in parts {
+        xml += &format!(
+            "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+            part.part_number,
+            escape(&part.e_tag)
+        );
+    }
+    xml += "</CompleteMultipartUpload>";
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initiate_result_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<InitiateMultipartUploadResult xmlns="http://doc.oss-cn-hangzhou.aliyuncs.com">
+    <Bucket>oss-example</Bucket>
+    <Key>multipart.data</Key>
+    <UploadId>0004B9894A22E5B1888A1E29F823****</UploadId>
+</InitiateMultipartUploadResult>"#;
+        let ret: InitiateMultipartUploadResult = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(ret.bucket, "oss-example");
+        assert_eq!(ret.key, "multipart.data");
+        assert_eq!(ret.upload_id, "0004B9894A22E5B1888A1E29F823****");
+    }
+    #[test]
+    fn complete_xml_test() {
+        let parts = [CompletedPart {
+            part_number: 1,
+            e_tag: "\"3349DC700140D7F86A0784842780****\"".to_owned(),
+        }];
+        assert_eq!(
+            complete_multipart_upload_xml(&parts),
+            "<CompleteMultipartUpload><Part><PartNumber>1</PartNumber>\
+             <ETag>&quot;3349DC700140D7F86A0784842780****&quot;</ETag></Part>\
+             </CompleteMultipartUpload>"
+        );
+    }
+}
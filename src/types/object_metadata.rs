@@ -0,0 +1,174 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use hyper::{
+    header::{CONTENT_LENGTH, CONTENT_TYPE, ETAG, LAST_MODIFIED},
+    HeaderMap,
+};
+
+use crate::{OSS_HASH_CRC64, OSS_META_PREFIX};
+
+use super::{xml::deserialize_from_str, ETag, Metas, ServerSideEncryption};
+
+const OSS_STORAGE_CLASS: &str = "x-oss-storage-class";
+const OSS_OBJECT_TYPE: &str = "x-oss-object-type";
+const OSS_VERSION_ID: &str = "x-oss-version-id";
+
+/// Storage classes of OSS objects and buckets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StorageClass {
+    Standard,
+    /// Infrequent Access
+    IA,
+    Archive,
+    ColdArchive,
+    DeepColdArchive,
+}
+
+impl StorageClass {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StorageClass::Standard => "Standard",
+            StorageClass::IA => "IA",
+            StorageClass::Archive => "Archive",
+            StorageClass::ColdArchive => "ColdArchive",
+            StorageClass::DeepColdArchive => "DeepColdArchive",
+        }
+    }
+}
+
+impl FromStr for StorageClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Standard" => Ok(StorageClass::Standard),
+            "IA" => Ok(StorageClass::IA),
+            "Archive" => Ok(StorageClass::Archive),
+            "ColdArchive" => Ok(StorageClass::ColdArchive),
+            "DeepColdArchive" => Ok(StorageClass::DeepColdArchive),
+            _ => Err(format!("unknown storage class {:?}", s)),
+        }
+    }
+}
+
+deserialize_from_str!(StorageClass);
+
+impl fmt::Display for StorageClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How an object was created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ObjectType {
+    /// Uploaded by PutObject
+    Normal,
+    /// Uploaded by multipart upload
+    Multipart,
+    /// Created by AppendObject
+    Appendable,
+    /// Created by PutSymlink
+    Symlink,
+}
+
+deserialize_from_str!(ObjectType);
+
+impl FromStr for ObjectType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Normal" => Ok(ObjectType::Normal),
+            "Multipart" => Ok(ObjectType::Multipart),
+            "Appendable" => Ok(ObjectType::Appendable),
+            "Symlink" => Ok(ObjectType::Symlink),
+            _ => Err(format!("unknown object type {:?}", s)),
+        }
+    }
+}
+
+/// Metadata of an object, as returned by GetObject and HeadObject.
+///
+/// Headers that are missing or fail to parse are left as `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ObjectMetadata {
+    pub etag: Option<ETag>,
+    pub content_length: Option<u64>,
+    pub content_type: Option<String>,
+    pub last_modified: Option<DateTime<Utc>>,
+    pub storage_class: Option<StorageClass>,
+    pub object_type: Option<ObjectType>,
+    pub version_id: Option<String>,
+    /// CRC64-ECMA of the whole object
+    pub crc64: Option<u64>,
+    pub server_side_encryption: Option<ServerSideEncryption>,
+    /// User metadata, keyed without the `x-oss-meta-` prefix
+    pub metas: Metas,
+}
+
+impl ObjectMetadata {
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name| headers.get(name).and_then(|val| val.to_str().ok());
+        let metas = headers
+            .iter()
+            .filter_map(|(key, val)| {
+                let key = key.as_str().strip_prefix(OSS_META_PREFIX)?;
+                Some((key.to_owned(), val.to_str().ok()?.to_owned()))
+            })
+            .collect();
+        ObjectMetadata {
+            etag: header(ETAG.as_str()).map(ETag::new),
+            content_length: header(CONTENT_LENGTH.as_str()).and_then(|val| val.parse().ok()),
+            content_type: header(CONTENT_TYPE.as_str()).map(str::to_owned),
+            last_modified: header(LAST_MODIFIED.as_str())
+                .and_then(|val| DateTime::parse_from_rfc2822(val).ok())
+                .map(|time| time.with_timezone(&Utc)),
+            storage_class: header(OSS_STORAGE_CLASS).and_then(|val| val.parse().ok()),
+            object_type: header(OSS_OBJECT_TYPE).and_then(|val| val.parse().ok()),
+            version_id: header(OSS_VERSION_ID).map(str::to_owned),
+            crc64: header(OSS_HASH_CRC64).and_then(|val| val.parse().ok()),
+            server_side_encryption: ServerSideEncryption::from_headers(headers),
+            metas,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_headers_test() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "etag",
+            "\"D41D8CD98F00B204E9800998ECF8427E\"".parse().unwrap(),
+        );
+        headers.insert("content-length", "344606".parse().unwrap());
+        headers.insert(
+            "last-modified",
+            "Fri, 24 Feb 2012 06:07:48 GMT".parse().unwrap(),
+        );
+        headers.insert("x-oss-storage-class", "IA".parse().unwrap());
+        headers.insert("x-oss-object-type", "Multipart".parse().unwrap());
+        headers.insert("x-oss-hash-crc64ecma", "316181249502703".parse().unwrap());
+        headers.insert("x-oss-meta-author", "oss".parse().unwrap());
+        let metadata = ObjectMetadata::from_headers(&headers);
+        assert_eq!(
+            metadata.etag,
+            Some(ETag::new("D41D8CD98F00B204E9800998ECF8427E"))
+        );
+        assert_eq!(metadata.content_length, Some(344606));
+        assert_eq!(
+            metadata.last_modified,
+            Some("2012-02-24T06:07:48Z".parse().unwrap())
+        );
+        assert_eq!(metadata.storage_class, Some(StorageClass::IA));
+        assert_eq!(metadata.object_type, Some(ObjectType::Multipart));
+        assert_eq!(metadata.version_id, None);
+        assert_eq!(metadata.crc64, Some(316181249502703));
+        assert_eq!(metadata.metas["author"], "oss");
+    }
+}
@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use super::{ETag, ObjectType, StorageClass};
+
+/// An object as OSS describes it in a ListObjects `<Contents>` element.
+///
+/// The crate has no listing call, the caller builds these or deserializes them from the
+/// `<Contents>` elements of a listing it fetched.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ObjectSummary {
+    pub key: String,
+    pub last_modified: DateTime<Utc>,
+    #[serde(rename = "ETag")]
+    pub etag: ETag,
+    pub size: u64,
+    pub storage_class: StorageClass,
+    #[serde(rename = "Type")]
+    pub object_type: Option<ObjectType>,
+    /// Tags of the object, which listings do not return, filled in by the caller
+    #[serde(skip)]
+    pub tags: Vec<(String, String)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contents_test() {
+        let xml = r#"<Contents>
+    <Key>fun/movie/001.avi</Key>
+    <LastModified>2012-02-24T08:43:07.000Z</LastModified>
+    <ETag>"5B3C1A2E053D763E1B002CC607C5A0FE1****"</ETag>
+    <Type>Normal</Type>
+    <Size>344606</Size>
+    <StorageClass>Standard</StorageClass>
+    <Owner>
+        <ID>0022012****</ID>
+        <DisplayName>user-example</DisplayName>
+    </Owner>
+</Contents>"#;
+        let ret: ObjectSummary = quick_xml::de::from_str(xml).unwrap();
+        assert_eq!(ret.key, "fun/movie/001.avi");
+        assert_eq!(ret.etag, ETag::new("5B3C1A2E053D763E1B002CC607C5A0FE1****"));
+        assert_eq!(ret.size, 344606);
+        assert_eq!(ret.object_type, Some(ObjectType::Normal));
+        assert!(ret.tags.is_empty());
+    }
+}
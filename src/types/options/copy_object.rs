@@ -0,0 +1,35 @@
+use headers_serializer::ToMaps;
+
+use crate::types::{Acl, Metas, Preconditions, ServerSideEncryption};
+#[derive(Clone, Debug, Default, PartialEq, ToMaps)]
+pub struct CopyObjectOptions {
+    /// <p>The bucket holding the source object, the bucket of the client when `None`.</p>
+    pub source_bucket: Option<String>,
+    /// <p>Specifies whether the copy overwrites objects of the same name. Set it to true to forbid overwriting.</p>
+    #[label("opts")]
+    pub x_oss_forbid_overwrite: Option<String>,
+    /// <p>Conditions on the modified time and ETag of the source object. The copy fails with 304 Not Modified or 412 Precondition Failed when they do not hold.</p>
+    pub copy_source_preconditions: Option<Preconditions>,
+    /// <p>How the metadata of the destination object is set.</p>
+    /// <p>Valid values: COPY, the metadata of the source object is copied, and REPLACE, the metadata given in the request is used.</p>
+    #[label("opts")]
+    pub x_oss_metadata_directive: Option<String>,
+    /// <p>The access control list (ACL) of the destination object, sent as x-oss-object-acl.</p>
+    pub object_acl: Option<Acl>,
+    /// <p>The storage class of the destination object.</p>
+    /// <p>Valid values: Standard, IA, Archive, and ColdArchive.</p>
+    #[label("opts")]
+    pub x_oss_storage_class: Option<String>,
+    /// <p>The tags of the destination object. Example: TagA=A&TagB=B.</p>
+    #[label("opts")]
+    pub x_oss_tagging: Option<String>,
+    /// <p>How the tags of the destination object are set.</p>
+    /// <p>Valid values: Copy and Replace</p>
+    #[label("opts")]
+    pub x_oss_tagging_directive: Option<String>,
+
+    /// <p>User metadata of the destination object, used with the REPLACE metadata directive.</p>
+    pub metas: Option<Metas>,
+    /// <p>The server-side encryption of the destination object.</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+}
@@ -0,0 +1,16 @@
+use headers_serializer::ToMaps;
+
+use crate::types::{Acl, DataRedundancyType, StorageClass};
+#[derive(Clone, Debug, Default, PartialEq, ToMaps)]
+pub struct CreateBucketOptions {
+    /// <p>The access control list (ACL) of the bucket, sent as x-oss-acl. Default value: private.</p>
+    pub acl: Option<Acl>,
+    /// <p>The ID of the resource group the bucket belongs to. The default resource group is used if it is not set.</p>
+    #[label("opts")]
+    pub x_oss_resource_group_id: Option<String>,
+
+    /// <p>The storage class of the bucket. Default value: Standard.</p>
+    pub storage_class: Option<StorageClass>,
+    /// <p>The redundancy type of the bucket. Default value: LRS.</p>
+    pub data_redundancy_type: Option<DataRedundancyType>,
+}
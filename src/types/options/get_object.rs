@@ -1,5 +1,7 @@
 use headers_serializer::ToMaps;
 
+use crate::types::{Preconditions, Progress, RateLimiter, TrafficLimit};
+
 #[derive(Clone, Debug, Default, PartialEq, ToMaps)]
 // #[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
 pub struct GetObjectOptions {
@@ -23,24 +25,11 @@ pub struct GetObjectOptions {
     pub response_content_encoding: Option<String>,
     /// <p>The range of data to be returned.</p>
     /// <p><li>If the value of Range is valid, OSS returns the response that includes the total size of the object and the range of data returned. For example, Content-Range: bytes 0~9/44 indicates that the total size of the object is 44 bytes, and the range of data returned is the first 10 bytes.</li><li>However, if the value of Range is invalid, the entire object is returned, and the response returned by OSS excludes Content-Range.</li></p>
+    /// <p>Use `get_object_range` to have an invalid range rejected instead.</p>
     #[label("opts")]
     pub range: Option<String>,
-    /// <p>If the time specified in this header is earlier than the object modified time or does not conform to the standards, OSS returns the object and 200 OK. If the time specified in this header is later than or the same as the object modified time, OSS returns 304 Not Modified.</p>
-    /// <p>The time must be in GMT. Example: Wed, 07 Oct 2020 14:47:53 GMT.</p>
-    #[label("opts")]
-    pub if_modified_since: Option<String>,
-    /// <p>If the time specified in this header is the same as or later than the object modified time, OSS returns the object and 200 OK. If the time specified in this header is earlier than the object modified time, OSS returns 412 Precondition Failed.</p>
-    /// <p>The time must be in GMT. Example: Wed, 07 Oct 2020 14:47:53 GMT.</p>
-    #[label("opts")]
-    pub if_unmodified_since: Option<String>,
-    /// <p>If the ETag specified in the request matches the ETag value of the object, OSS transmits the object and returns 200 OK. If the ETag specified in the request does not match the ETag value of the object, OSS returns 412 Precondition Failed.</p>
-    /// <p>The ETag value of an object is used to check whether the content of the object has changed. You can check data integrity by using the ETag value.</p>
-    #[label("opts")]
-    pub if_match: Option<String>,
-    /// <p>If the ETag specified in the request does not match the ETag value of the object, OSS transmits the object and returns 200 OK. If the ETag specified in the request matches the ETag value of the object, OSS returns 304 Not Modified.</p>
-    /// <p>You can specify both the If-Match and If-None-Match headers in a request.</p>
-    #[label("opts")]
-    pub if_none_match: Option<String>,
+    /// <p>Conditions on the modified time and ETag of the object. Use `get_object_conditional` to get 304 Not Modified and 412 Precondition Failed as typed outcomes.</p>
+    pub preconditions: Option<Preconditions>,
     /// <p>The encoding type at the client side.</p>
     /// <p>If you want an object to be returned in the GZIP format, you must include the Accept-Encoding:gzip header in your request. OSS determines whether to return the object compressed in the GZIP format. OSS evaluates the decision based on the Content-Type header and whether the size of the object is larger than or equal to 1 KB.</p>
     /// <p>Note
@@ -49,5 +38,14 @@ pub struct GetObjectOptions {
     /// </p>
     #[label("opts")]
     pub accept_encoding: Option<String>,
+    /// <p>Verify the CRC64-ECMA of the downloaded data against the x-oss-hash-crc64ecma header returned by OSS. Enabled unless set to `Some(false)`.</p>
+    /// <p>The check is skipped for partial content. A mismatch surfaces as an error when the end of the body is read.</p>
+    pub check_crc64: Option<bool>,
+    /// <p>Listener notified of the bytes transferred.</p>
+    pub progress: Option<Progress>,
+    /// <p>Client-side limiter the transferred bytes are held back by.</p>
+    pub rate_limiter: Option<RateLimiter>,
+    /// <p>Server-side bandwidth limit of this request, sent as x-oss-traffic-limit.</p>
+    pub traffic_limit: Option<TrafficLimit>,
     // pub metas: Option<Metas>,
 }
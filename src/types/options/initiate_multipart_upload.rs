@@ -0,0 +1,37 @@
+use headers_serializer::ToMaps;
+
+use crate::types::{Metas, ServerSideEncryption};
+#[derive(Clone, Debug, Default, PartialEq, ToMaps)]
+pub struct InitiateMultipartUploadOptions {
+    /// <p>The web page caching behavior that is specified when the object is downloaded.</p>
+    #[label("opts")]
+    pub cache_control: Option<String>,
+    /// <p>The name of the object when the object is downloaded.</p>
+    #[label("opts")]
+    pub content_disposition: Option<String>,
+    /// <p>The content encoding type of the object during the download. </p>
+    #[label("opts")]
+    pub content_encoding: Option<String>,
+    /// <p>The content type of the object.</p>
+    #[label("opts")]
+    pub content_type: Option<String>,
+    /// <p>The time period after which the response is considered expired.</p>
+    #[label("opts")]
+    pub expires: Option<String>,
+    /// <p>Specifies whether the upload overwrites objects of the same name. Set it to true to forbid overwriting.</p>
+    #[label("opts")]
+    pub x_oss_forbid_overwrite: Option<String>,
+    /// <p>The storage class of the object.</p>
+    /// <p>Valid values: Standard, IA, Archive, and ColdArchive.</p>
+    #[label("opts")]
+    pub x_oss_storage_class: Option<String>,
+    /// <p>The object tag. You can configure multiple tags for the object. Example: TagA=A&TagB=B.</p>
+    #[label("opts")]
+    pub x_oss_tagging: Option<String>,
+
+    /// <p>User metadata of the object, sent as x-oss-meta-* headers.</p>
+    pub metas: Option<Metas>,
+    /// <p>The server-side encryption method that is used when OSS creates the object.</p>
+    /// <p>If you specify this parameter, the uploaded object is encrypted and stored, and the algorithm is returned in the x-oss-server-side-encryption response header.</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
+}
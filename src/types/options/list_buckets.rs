@@ -0,0 +1,10 @@
+/// Query parameters of `list_buckets`, for listing a page of buckets at a time.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ListBucketsOptions {
+    /// <p>Only buckets whose names start with the prefix are listed.</p>
+    pub prefix: Option<String>,
+    /// <p>Buckets whose names come after the marker in alphabetical order are listed. Pass the `next_marker` of a truncated result to list the next page.</p>
+    pub marker: Option<String>,
+    /// <p>The maximum number of buckets listed, from 1 to 1000. Default value: 100.</p>
+    pub max_keys: Option<u32>,
+}
@@ -1,7 +1,17 @@
+mod copy_object;
+mod create_bucket;
 mod get_object;
 mod head_object;
+mod initiate_multipart_upload;
+mod list_buckets;
 mod put_object;
+mod upload_part;
 
+pub use copy_object::CopyObjectOptions;
+pub use create_bucket::CreateBucketOptions;
 pub use get_object::GetObjectOptions;
 pub use head_object::HeadObjectOptions;
+pub use initiate_multipart_upload::InitiateMultipartUploadOptions;
+pub use list_buckets::ListBucketsOptions;
 pub use put_object::PutObjectOptions;
+pub use upload_part::UploadPartOptions;
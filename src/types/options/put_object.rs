@@ -1,6 +1,6 @@
 use headers_serializer::ToMaps;
 
-use crate::types::Metas;
+use crate::types::{Acl, Metas, Progress, RateLimiter, ServerSideEncryption, TrafficLimit};
 #[derive(Clone, Debug, Default, PartialEq, ToMaps)]
 // #[cfg_attr(feature = "deserialize_structs", derive(Deserialize))]
 pub struct PutObjectOptions {
@@ -13,10 +13,27 @@ pub struct PutObjectOptions {
     /// <p>The content encoding type of the object during the download. </p>
     #[label("opts")]
     pub content_encoding: Option<String>,
+    /// <p>The content type of the object. When uploading from a file and this is left unset, it is guessed from the file extension.</p>
+    #[label("opts")]
+    pub content_type: Option<String>,
     /// <p>The MD5 hash of the object you want to upload. The value of Content-MD5 is calculated based on the MD5 algorithm. After the Content-MD5 request header is uploaded, OSS calculates the MD5 hash of the received object and checks whether the calculated MD5 hash is the same as the Content-MD5 value provided in the request.</p>
     /// <p>To ensure data integrity, OSS provides multiple methods for you to check the MD5 hashes of the data. To perform MD5 verification based on the Content-MD5 header, add the Content-MD5 header to the request.</p>
     #[label("opts")]
     pub content_md5: Option<String>,
+    /// <p>Compute Content-MD5 from the payload when `content_md5` is not given.</p>
+    /// <p>Payloads other than `Payload::Buffer` have to be buffered in memory to be hashed before they are sent.</p>
+    pub compute_content_md5: bool,
+    /// <p>Verify the CRC64-ECMA of the uploaded data against the x-oss-hash-crc64ecma header returned by OSS. Enabled unless set to `Some(false)`.</p>
+    pub check_crc64: Option<bool>,
+    /// <p>Size of the parts a payload of unknown length is uploaded in, 8 MiB by default and 100 KiB at least.</p>
+    /// <p>Such a payload is sent with a single PutObject request when it fits in one part, and as a multipart upload of at most 10000 parts otherwise.</p>
+    pub part_size: Option<u64>,
+    /// <p>Listener notified of the bytes transferred.</p>
+    pub progress: Option<Progress>,
+    /// <p>Client-side limiter the transferred bytes are held back by.</p>
+    pub rate_limiter: Option<RateLimiter>,
+    /// <p>Server-side bandwidth limit of this request, sent as x-oss-traffic-limit.</p>
+    pub traffic_limit: Option<TrafficLimit>,
     /// <p>The ETag that is generated when an object is created. ETags are used to identify the content of the objects.</p>
     /// <p><li>If an object is created by using a PutObject request, the ETag value is the MD5 hash of the object content.</li>
     /// <li>If an object is created by using other methods, the ETag value is the UUID of the object content.</li></p>
@@ -32,19 +49,9 @@ pub struct PutObjectOptions {
     /// <p>If you specify the x-oss-forbid-overwrite request header, the queries per second (QPS) performance of OSS may be degraded. If you want to use the x-oss-forbid-overwrite request header to perform a large number of operations (QPS greater than 1,000), submit a ticket.</p>
     #[label("opts")]
     pub x_oss_forbid_overwrite: Option<String>,
-    /// <p>The server-side encryption method that is used when OSS creates the object.</p>
-    /// <p>Valid values: AES256 and KMS</p>
-    /// <p>If you specify this parameter, this parameter is returned in the response header and the uploaded object is encrypted and stored. When you download the encrypted object, the x-oss-server-side-encryption header is included in the response and the header value is set to the algorithm used to encrypt the object.</p>
-    #[label("opts")]
-    pub x_oss_server_side_encryption: Option<String>,
-    /// <p>The ID of the customer master key (CMK) hosted in KMS.</p>
-    /// <p>This parameter is valid only when x-oss-server-side-encryption is set to KMS.</p>
-    #[label("opts")]
-    pub x_oss_server_side_encryption_key_id: Option<String>,
-    /// <p>The access control list (ACL) of the object you want to create.</p>
-    /// <p>Valid values: public-read, private, and public-read-write</p>
-    #[label("opts")]
-    pub x_oss_object_acl: Option<String>,
+    /// <p>The access control list (ACL) of the object you want to create, sent as x-oss-object-acl.</p>
+    /// <p>The ACL of the bucket applies when it is not set or set to `Acl::Default`.</p>
+    pub object_acl: Option<Acl>,
     /// <p>The storage class of an object.</p>
     /// <p>If you specify the storage class when you upload the object, the specified storage class applies regardless of the storage class of the bucket that contains the object. If you set x-oss-storage-class to Standard when you upload an object to an IA bucket, the object is stored as a Standard object.</p>
     /// <p>Valid values: Standard, IA, Archive, and ColdArchive.</p>
@@ -59,4 +66,7 @@ pub struct PutObjectOptions {
     /// <p>If the PutObject request contains a parameter prefixed with x-oss-meta-*, the parameter is considered to be user metadata. Example: x-oss-meta-location. An object can have multiple similar parameters. However, the total size of the user metadata cannot exceed 8 KB.</p>
     /// <p>Metadata supports hyphens (-), digits, and letters. Uppercase letters are converted to lowercase letters, and other characters such as underscores (_) are not supported.</p>
     pub metas: Option<Metas>,
+    /// <p>The server-side encryption method that is used when OSS creates the object.</p>
+    /// <p>If you specify this parameter, the uploaded object is encrypted and stored, and the algorithm is returned in the x-oss-server-side-encryption response header.</p>
+    pub server_side_encryption: Option<ServerSideEncryption>,
 }
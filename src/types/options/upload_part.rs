@@ -0,0 +1,20 @@
+use headers_serializer::ToMaps;
+
+use crate::types::{Progress, RateLimiter, TrafficLimit};
+#[derive(Clone, Debug, Default, PartialEq, ToMaps)]
+pub struct UploadPartOptions {
+    /// <p>The MD5 hash of the part. OSS checks the received data against it.</p>
+    #[label("opts")]
+    pub content_md5: Option<String>,
+    /// <p>Compute Content-MD5 from the payload when `content_md5` is not given.</p>
+    /// <p>Payloads other than `Payload::Buffer` have to be buffered in memory to be hashed before they are sent.</p>
+    pub compute_content_md5: bool,
+    /// <p>Verify the CRC64-ECMA of the uploaded part against the x-oss-hash-crc64ecma header returned by OSS. Enabled unless set to `Some(false)`.</p>
+    pub check_crc64: Option<bool>,
+    /// <p>Listener notified of the bytes transferred and of the completed part.</p>
+    pub progress: Option<Progress>,
+    /// <p>Client-side limiter the transferred bytes are held back by.</p>
+    pub rate_limiter: Option<RateLimiter>,
+    /// <p>Server-side bandwidth limit of this request, sent as x-oss-traffic-limit.</p>
+    pub traffic_limit: Option<TrafficLimit>,
+}
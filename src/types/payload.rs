@@ -1,30 +1,269 @@
-use bytes::Bytes;
+use std::{
+    fmt, io,
+    io::SeekFrom,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use base64::encode;
+use bytes::{Bytes, BytesMut};
+use crypto::{digest::Digest, md5::Md5};
+use futures::{stream, TryStreamExt};
 use hyper::Body;
+use tokio::{
+    fs::File,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+use tokio_util::io::ReaderStream;
+
+use crate::{ByteStream, Crc64, Progress, RateLimiter};
 
-use crate::ByteStream;
+/// Chunk size `Buffer` payloads are split into when wrapped by stream adapters.
+const CHUNK_SIZE: usize = 64 * 1024;
 
 /// Possible payloads included in a `Request`.
+///
+/// Every variant but `Stream` can be read again, which retries and re-reads of multipart
+/// parts depend on.
 #[derive(Debug)]
 pub enum Payload {
     /// Transfer payload in a single chunk
     Buffer(Bytes),
-    /// Transfer payload in multiple chunks
+    /// Transfer payload in multiple chunks, read only once
     Stream(ByteStream),
+    /// Transfer `len` bytes of the file at `path`, starting at `offset`
+    File {
+        path: PathBuf,
+        offset: u64,
+        len: u64,
+    },
+    /// Transfer the content of a source that can be read from the start again
+    Source(Arc<dyn PayloadSource>),
+}
+
+/// Content that can be reopened or rewound to be read again from the start.
+pub trait PayloadSource: Send + Sync {
+    /// Length of the content in bytes, if known.
+    fn content_length(&self) -> Option<u64>;
+    /// A stream over the whole content, from its first byte.
+    ///
+    /// Called once per attempt to send the payload, errors are reported through the stream.
+    fn open(&self) -> ByteStream;
+}
+
+impl fmt::Debug for dyn PayloadSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "<PayloadSource content_length={:?}>",
+            self.content_length()
+        )
+    }
+}
+
+/// A replayable payload with `map` applied to the stream of every read.
+struct MappedSource {
+    // Never a `Stream`, the lock only makes the type `Sync`.
+    inner: Mutex<Payload>,
+    map: Box<dyn Fn(ByteStream) -> ByteStream + Send + Sync>,
+}
+
+impl PayloadSource for MappedSource {
+    fn content_length(&self) -> Option<u64> {
+        self.inner.lock().unwrap().len().map(|len| len as u64)
+    }
+    fn open(&self) -> ByteStream {
+        let stream = self
+            .inner
+            .lock()
+            .unwrap()
+            .try_clone()
+            .expect("mapped payloads are replayable")
+            .into_chunked_stream();
+        (self.map)(stream)
+    }
 }
+
 impl Payload {
+    /// A payload reading `source` anew for every attempt.
+    pub fn from_source<S>(source: S) -> Self
+    where
+        S: PayloadSource + 'static,
+    {
+        Payload::Source(Arc::new(source))
+    }
+    /// The whole file at `path`.
+    pub async fn from_path<P: Into<PathBuf>>(path: P) -> io::Result<Self> {
+        let path = path.into();
+        let len = tokio::fs::metadata(&path).await?.len();
+        Ok(Payload::File {
+            path,
+            offset: 0,
+            len,
+        })
+    }
     /// Convert `SignedRequestPayload` into a hyper `Body`
     pub fn into_body(self) -> Body {
         match self {
             Payload::Buffer(bytes) => Body::from(bytes),
-            Payload::Stream(stream) => Body::wrap_stream(stream),
+            payload => Body::wrap_stream(payload.into_stream()),
+        }
+    }
+    /// A copy of the payload to send again, `None` for a `Stream` that can't be replayed.
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        match self {
+            Payload::Buffer(bytes) => Some(Payload::Buffer(bytes.clone())),
+            Payload::Stream(_) => None,
+            Payload::File { path, offset, len } => Some(Payload::File {
+                path: path.clone(),
+                offset: *offset,
+                len: *len,
+            }),
+            Payload::Source(source) => Some(Payload::Source(source.clone())),
         }
     }
     pub(crate) fn len(&self) -> Option<usize> {
         match self {
             Payload::Buffer(bytes) => Some(bytes.len()),
             Payload::Stream(stream) => stream.size_hint().1,
+            Payload::File { len, .. } => Some(*len as usize),
+            Payload::Source(source) => source.content_length().map(|len| len as usize),
+        }
+    }
+    /// Collect the payload into a single `Buffer`, so that it can be hashed before sending.
+    pub(crate) async fn buffered(self) -> io::Result<Self> {
+        if let Payload::Buffer(bytes) = self {
+            return Ok(Payload::Buffer(bytes));
+        }
+        let capacity = self.len().unwrap_or_default();
+        let buf = self
+            .into_stream()
+            .try_fold(
+                BytesMut::with_capacity(capacity),
+                |mut buf, chunk| async move {
+                    buf.extend_from_slice(&chunk);
+                    Ok(buf)
+                },
+            )
+            .await?;
+        Ok(Payload::Buffer(buf.freeze()))
+    }
+    /// Apply `map` to the stream of the payload, keeping it replayable unless it is a `Stream`.
+    pub(crate) fn map_stream<F>(self, map: F) -> Self
+    where
+        F: Fn(ByteStream) -> ByteStream + Send + Sync + 'static,
+    {
+        match self {
+            Payload::Stream(stream) => Payload::Stream(map(stream)),
+            inner => Payload::from_source(MappedSource {
+                inner: Mutex::new(inner),
+                map: Box::new(map),
+            }),
+        }
+    }
+    /// Feed the payload into `crc`, immediately for a `Buffer`, as it is sent otherwise.
+    ///
+    /// `crc` starts over whenever a replayable payload is read again.
+    pub(crate) fn hash_crc64(self, crc: &Arc<Mutex<Crc64>>) -> Self {
+        match self {
+            Payload::Buffer(bytes) => {
+                crc.lock().unwrap().update(&bytes);
+                Payload::Buffer(bytes)
+            }
+            payload => {
+                let crc = crc.clone();
+                payload.map_stream(move |stream| {
+                    *crc.lock().unwrap() = Crc64::new();
+                    stream.hash_crc64(crc.clone())
+                })
+            }
+        }
+    }
+    /// Report the bytes of the payload to `progress` as they are sent.
+    pub(crate) fn with_progress(self, progress: Progress) -> Self {
+        let total = self.len().map(|len| len as u64);
+        self.map_stream(move |stream| stream.with_progress(progress.clone(), total))
+    }
+    /// Send the payload no faster than `limiter` allows.
+    pub(crate) fn throttle(self, limiter: RateLimiter) -> Self {
+        self.map_stream(move |stream| stream.throttle(limiter.clone()))
+    }
+    /// A `Buffer` is split into fixed size chunks so that stream adapters observe it gradually.
+    fn into_chunked_stream(self) -> ByteStream {
+        match self {
+            Payload::Buffer(bytes) => {
+                let len = bytes.len();
+                let chunks = (0..len).step_by(CHUNK_SIZE).map(move |start| {
+                    Ok(bytes.slice(start..std::cmp::min(start + CHUNK_SIZE, len)))
+                });
+                ByteStream::new_with_size(stream::iter(chunks), len)
+            }
+            payload => payload.into_stream(),
         }
     }
+    /// The payload as a stream of bytes.
+    pub(crate) fn into_stream(self) -> ByteStream {
+        match self {
+            Payload::Buffer(bytes) => ByteStream::from(bytes.to_vec()),
+            Payload::Stream(stream) => stream,
+            Payload::File { path, offset, len } => {
+                let open = async move {
+                    let mut file = File::open(path).await?;
+                    file.seek(SeekFrom::Start(offset)).await?;
+                    Ok::<_, io::Error>(ReaderStream::new(file.take(len)))
+                };
+                let stream = stream::once(open).try_flatten();
+                ByteStream::new_with_size(stream, len as usize)
+            }
+            Payload::Source(source) => source.open(),
+        }
+    }
+    /// The base64 encoded MD5 digest of a `Buffer` payload, as expected by the Content-MD5 header.
+    pub(crate) fn content_md5(&self) -> Option<String> {
+        match self {
+            Payload::Buffer(bytes) => {
+                let mut hasher = Md5::new();
+                hasher.input(bytes);
+                let mut digest = [0u8; 16];
+                hasher.result(&mut digest);
+                Some(encode(digest))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl From<&'static [u8]> for Payload {
+    fn from(bytes: &'static [u8]) -> Self {
+        Payload::Buffer(Bytes::from_static(bytes))
+    }
+}
+impl From<&'static str> for Payload {
+    fn from(s: &'static str) -> Self {
+        Payload::Buffer(Bytes::from_static(s.as_bytes()))
+    }
+}
+impl From<Vec<u8>> for Payload {
+    fn from(bytes: Vec<u8>) -> Self {
+        Payload::Buffer(bytes.into())
+    }
+}
+impl From<String> for Payload {
+    fn from(s: String) -> Self {
+        Payload::Buffer(s.into())
+    }
+}
+impl From<Bytes> for Payload {
+    fn from(bytes: Bytes) -> Self {
+        Payload::Buffer(bytes)
+    }
+}
+/// Read from the current position of `file` to its end, once and of unknown length. Prefer
+/// `Payload::File` for uploads that may be retried.
+impl From<File> for Payload {
+    fn from(file: File) -> Self {
+        Payload::Stream(ByteStream::new(ReaderStream::new(file)))
+    }
 }
 
 impl Default for Payload {
@@ -32,3 +271,76 @@ impl Default for Payload {
         Self::Buffer(Bytes::new())
     }
 }
+
+#[test]
+fn test_content_md5() {
+    let payload = Payload::Buffer(Bytes::from_static(b"0123456789"));
+    assert_eq!(
+        payload.content_md5().as_deref(),
+        Some("eB5eJF1ptWaXm4bijSPyxw==")
+    );
+}
+
+#[tokio::test]
+async fn test_buffered_content_md5() {
+    let stream = crate::ByteStream::from(b"0123456789".to_vec());
+    let payload = Payload::Stream(stream).buffered().await.unwrap();
+    assert_eq!(
+        payload.content_md5().as_deref(),
+        Some("eB5eJF1ptWaXm4bijSPyxw==")
+    );
+}
+
+#[test]
+fn test_stream_payload_len() {
+    let chunks = (0..20).map(|_| Ok(Bytes::from(vec![0u8; 64])));
+    let payload = Payload::Stream(crate::ByteStream::new(futures::stream::iter(chunks)));
+    assert_eq!(payload.len(), None);
+    let chunks = (0..20).map(|_| Ok(Bytes::from(vec![0u8; 64])));
+    let stream = crate::ByteStream::new_with_size(futures::stream::iter(chunks), 20 * 64);
+    assert_eq!(Payload::Stream(stream).len(), Some(20 * 64));
+    assert_eq!(Payload::Stream(vec![0u8; 64].into()).len(), Some(64));
+}
+
+#[tokio::test]
+async fn test_file_payload() {
+    let path = std::env::temp_dir().join(format!("oss-payload-test-{}", std::process::id()));
+    tokio::fs::write(&path, b"0123456789").await.unwrap();
+    let payload = Payload::File {
+        path: path.clone(),
+        offset: 2,
+        len: 5,
+    };
+    assert_eq!(payload.len(), Some(5));
+    let copy = payload.try_clone().unwrap();
+    for payload in [payload, copy] {
+        match payload.buffered().await.unwrap() {
+            Payload::Buffer(bytes) => assert_eq!(&bytes[..], b"23456"),
+            _ => unreachable!(),
+        }
+    }
+    tokio::fs::remove_file(&path).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_mapped_payload_replay() {
+    let crc = Arc::new(Mutex::new(Crc64::new()));
+    let payload = Payload::from(&b"0123456789"[..])
+        .throttle(RateLimiter::new(1 << 20))
+        .hash_crc64(&crc);
+    assert!(matches!(payload, Payload::Source(_)));
+    for _ in 0..2 {
+        let bytes: Vec<Bytes> = payload
+            .try_clone()
+            .unwrap()
+            .into_stream()
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(bytes.concat(), b"0123456789");
+        assert_eq!(crc.lock().unwrap().value(), Crc64::checksum(b"0123456789"));
+    }
+    assert!(Payload::Stream(ByteStream::from(vec![1u8]))
+        .try_clone()
+        .is_none());
+}
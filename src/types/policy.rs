@@ -0,0 +1,305 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::{Error, Result};
+
+/// The only policy language version OSS accepts.
+const POLICY_VERSION: &str = "1";
+
+/// Condition values by operator then key, such as `IpAddress` then `acs:SourceIp`.
+pub type Conditions = BTreeMap<String, BTreeMap<String, Vec<String>>>;
+
+/// A bucket policy, granting or denying principals actions on the bucket and its objects.
+///
+/// Fields that take a list also accept a single string, and are always written as lists.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PolicyDocument {
+    pub version: String,
+    pub statement: Vec<Statement>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Effect {
+    Allow,
+    Deny,
+}
+
+/// A statement of a `PolicyDocument`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct Statement {
+    pub effect: Effect,
+    /// Actions such as `oss:GetObject`, or `oss:*` for all
+    #[serde(deserialize_with = "one_or_many")]
+    pub action: Vec<String>,
+    /// Resources such as `acs:oss:*:*:bucket/prefix*`
+    #[serde(deserialize_with = "one_or_many")]
+    pub resource: Vec<String>,
+    /// Account or RAM user IDs, `*` for everyone
+    #[serde(
+        default,
+        skip_serializing_if = "Vec::is_empty",
+        deserialize_with = "one_or_many"
+    )]
+    pub principal: Vec<String>,
+    #[serde(
+        default,
+        skip_serializing_if = "BTreeMap::is_empty",
+        deserialize_with = "condition"
+    )]
+    pub condition: Conditions,
+}
+
+impl PolicyDocument {
+    pub fn new(statement: Vec<Statement>) -> Self {
+        Self {
+            version: POLICY_VERSION.to_owned(),
+            statement,
+        }
+    }
+    /// Check the version, that each statement has actions and resources, and that the
+    /// resources are `acs:oss:<region>:<account>:<bucket>[/<object>]` ARNs.
+    pub fn validate(&self) -> Result<()> {
+        if self.version != POLICY_VERSION {
+            return Err(Error::invalid_argument(format!(
+                "unsupported policy version {:?}",
+                self.version
+            )));
+        }
+        if self.statement.is_empty() {
+            return Err(Error::invalid_argument(
+                "a policy takes at least 1 statement",
+            ));
+        }
+        for statement in &self.statement {
+            if statement.action.is_empty() {
+                return Err(Error::invalid_argument("a policy statement has no actions"));
+            }
+            if statement.resource.is_empty() {
+                return Err(Error::invalid_argument(
+                    "a policy statement has no resources",
+                ));
+            }
+            for resource in &statement.resource {
+                validate_resource(resource).map_err(|err| {
+                    Error::invalid_argument(format!("resource {:?}: {}", resource, err))
+                })?;
+            }
+        }
+        Ok(())
+    }
+    /// Body of a PutBucketPolicy request.
+    pub(crate) fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a policy always serializes")
+    }
+}
+
+impl Statement {
+    pub fn new<A, R>(effect: Effect, action: A, resource: R) -> Self
+    where
+        A: IntoIterator,
+        A::Item: Into<String>,
+        R: IntoIterator,
+        R::Item: Into<String>,
+    {
+        Self {
+            effect,
+            action: action.into_iter().map(Into::into).collect(),
+            resource: resource.into_iter().map(Into::into).collect(),
+            principal: vec![],
+            condition: BTreeMap::new(),
+        }
+    }
+    pub fn with_principal<S: Into<String>>(mut self, principal: S) -> Self {
+        self.principal.push(principal.into());
+        self
+    }
+    /// Add the condition `operator` on `key`, such as `StringLike` on `acs:Referer`.
+    pub fn with_condition<S1, S2, V>(mut self, operator: S1, key: S2, values: V) -> Self
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        V: IntoIterator,
+        V::Item: Into<String>,
+    {
+        self.condition
+            .entry(operator.into())
+            .or_default()
+            .insert(key.into(), values.into_iter().map(Into::into).collect());
+        self
+    }
+}
+
+/// Check a resource is `acs:oss:<region>:<account>:<bucket>[/<object>]`, where the region
+/// and account are usually `*` and the object may end with a `*` wildcard.
+fn validate_resource(resource: &str) -> std::result::Result<(), &'static str> {
+    let rest = resource
+        .strip_prefix("acs:oss:")
+        .ok_or("expected the prefix \"acs:oss:\"")?;
+    let parts: Vec<_> = rest.splitn(3, ':').collect();
+    if parts.len() != 3 {
+        return Err("expected acs:oss:<region>:<account>:<bucket>[/<object>]");
+    }
+    if parts[0].is_empty() || parts[1].is_empty() {
+        return Err("empty region or account, use \"*\" for any");
+    }
+    if parts[1] != "*" && !parts[1].bytes().all(|b| b.is_ascii_digit()) {
+        return Err("the account is neither \"*\" nor an account ID");
+    }
+    let bucket = parts[2].split('/').next().unwrap_or_default();
+    if bucket == "*" {
+        return Ok(());
+    }
+    let valid = (3..=63).contains(&bucket.len())
+        && bucket
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+        && !bucket.starts_with('-')
+        && !bucket.ends_with('-');
+    if valid {
+        Ok(())
+    } else {
+        Err("invalid bucket name")
+    }
+}
+
+/// A string or a list of strings, as policy fields take either.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(Scalar),
+    Many(Vec<Scalar>),
+}
+
+/// Condition values may be written as booleans or numbers, such as `"acs:SecureTransport": true`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scalar {
+    String(String),
+    Bool(bool),
+    Number(serde_json::Number),
+}
+
+impl From<OneOrMany> for Vec<String> {
+    fn from(values: OneOrMany) -> Self {
+        let to_string = |value| match value {
+            Scalar::String(value) => value,
+            Scalar::Bool(value) => value.to_string(),
+            Scalar::Number(value) => value.to_string(),
+        };
+        match values {
+            OneOrMany::One(value) => vec![to_string(value)],
+            OneOrMany::Many(values) => values.into_iter().map(to_string).collect(),
+        }
+    }
+}
+
+fn one_or_many<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(OneOrMany::deserialize(deserializer)?.into())
+}
+
+fn condition<'de, D>(deserializer: D) -> std::result::Result<Conditions, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let condition = BTreeMap::<String, BTreeMap<String, OneOrMany>>::deserialize(deserializer)?;
+    Ok(condition
+        .into_iter()
+        .map(|(operator, keys)| {
+            let keys = keys
+                .into_iter()
+                .map(|(key, values)| (key, values.into()))
+                .collect();
+            (operator, keys)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_document_test() {
+        let json = r#"{
+  "Version": "1",
+  "Statement": [
+    {
+      "Effect": "Allow",
+      "Action": ["oss:GetObject", "oss:ListObjects"],
+      "Principal": "*",
+      "Resource": "acs:oss:*:*:examplebucket/public/*",
+      "Condition": {
+        "IpAddress": {"acs:SourceIp": ["192.168.0.0/16", "10.0.0.1"]},
+        "Bool": {"acs:SecureTransport": true}
+      }
+    },
+    {
+      "Effect": "Deny",
+      "Action": "oss:*",
+      "Principal": ["20214760404935xxxx"],
+      "Resource": ["acs:oss:*:*:examplebucket", "acs:oss:*:*:examplebucket/*"]
+    }
+  ]
+}"#;
+        let policy: PolicyDocument = serde_json::from_str(json).unwrap();
+        assert_eq!(policy.statement.len(), 2);
+        let allow = &policy.statement[0];
+        assert_eq!(allow.effect, Effect::Allow);
+        assert_eq!(allow.principal, vec!["*"]);
+        assert_eq!(allow.resource, vec!["acs:oss:*:*:examplebucket/public/*"]);
+        assert_eq!(allow.condition["Bool"]["acs:SecureTransport"], vec!["true"]);
+        assert_eq!(policy.statement[1].action, vec!["oss:*"]);
+        policy.validate().unwrap();
+
+        let parsed: PolicyDocument = serde_json::from_str(&policy.to_json()).unwrap();
+        assert_eq!(parsed, policy);
+
+        let built = PolicyDocument::new(vec![Statement::new(
+            Effect::Deny,
+            vec!["oss:*"],
+            vec!["acs:oss:*:*:examplebucket", "acs:oss:*:*:examplebucket/*"],
+        )
+        .with_principal("20214760404935xxxx")]);
+        assert_eq!(built.statement[0], policy.statement[1]);
+    }
+    #[test]
+    fn validate_test() {
+        let policy = |resource: &str| {
+            PolicyDocument::new(vec![Statement::new(
+                Effect::Allow,
+                vec!["oss:GetObject"],
+                vec![resource],
+            )])
+        };
+        assert!(policy("acs:oss:*:*:examplebucket").validate().is_ok());
+        assert!(policy("acs:oss:*:1234567890:examplebucket/dir/*")
+            .validate()
+            .is_ok());
+        assert!(policy("acs:oss:*:*:*").validate().is_ok());
+        assert!(policy("acs:ecs:*:*:examplebucket").validate().is_err());
+        assert!(policy("acs:oss:*:*").validate().is_err());
+        assert!(policy("acs:oss::*:examplebucket").validate().is_err());
+        assert!(policy("acs:oss:*:someone:examplebucket")
+            .validate()
+            .is_err());
+        assert!(policy("acs:oss:*:*:Example_Bucket/*").validate().is_err());
+        assert!(policy("acs:oss:*:*:-bucket").validate().is_err());
+
+        let mut unversioned = policy("acs:oss:*:*:examplebucket");
+        unversioned.version = "2".to_owned();
+        assert!(unversioned.validate().is_err());
+        assert!(PolicyDocument::new(vec![]).validate().is_err());
+        let no_actions = PolicyDocument::new(vec![Statement::new(
+            Effect::Allow,
+            Vec::<String>::new(),
+            vec!["acs:oss:*:*:examplebucket"],
+        )]);
+        assert!(no_actions.validate().is_err());
+    }
+}
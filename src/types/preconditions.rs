@@ -0,0 +1,160 @@
+use std::{convert::Infallible, fmt, str::FromStr};
+
+use chrono::{DateTime, Utc};
+use hyper::StatusCode;
+
+use super::{xml::deserialize_from_str, Result};
+
+/// Format of HTTP dates, the IMF-fixdate of RFC 7231.
+const HTTP_DATE: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// An entity tag identifying the content of an object.
+///
+/// OSS sends ETags quoted, the quotes are not part of the value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ETag(String);
+
+impl ETag {
+    pub fn new<S: AsRef<str>>(tag: S) -> Self {
+        Self(tag.as_ref().trim_matches('"').to_owned())
+    }
+    /// The value without quotes.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl FromStr for ETag {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+deserialize_from_str!(ETag);
+
+/// Quoted, as sent in headers.
+impl fmt::Display for ETag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.0)
+    }
+}
+
+/// Conditions an object has to meet for a request to be carried out.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Preconditions {
+    /// Only if the object was modified after this time, otherwise 304 Not Modified.
+    pub if_modified_since: Option<DateTime<Utc>>,
+    /// Only if the object was not modified after this time, otherwise 412 Precondition Failed.
+    pub if_unmodified_since: Option<DateTime<Utc>>,
+    /// Only if the ETag of the object matches, otherwise 412 Precondition Failed.
+    pub if_match: Option<ETag>,
+    /// Only if the ETag of the object does not match, otherwise 304 Not Modified.
+    pub if_none_match: Option<ETag>,
+}
+
+impl Preconditions {
+    /// The conditional request headers.
+    pub(crate) fn to_headers(&self) -> Vec<(&'static str, String)> {
+        self.headers_named([
+            "if-modified-since",
+            "if-unmodified-since",
+            "if-match",
+            "if-none-match",
+        ])
+    }
+    /// The headers applying the conditions to the source of a copy.
+    pub(crate) fn to_copy_source_headers(&self) -> Vec<(&'static str, String)> {
+        self.headers_named([
+            "x-oss-copy-source-if-modified-since",
+            "x-oss-copy-source-if-unmodified-since",
+            "x-oss-copy-source-if-match",
+            "x-oss-copy-source-if-none-match",
+        ])
+    }
+    fn headers_named(&self, names: [&'static str; 4]) -> Vec<(&'static str, String)> {
+        let [modified_since, unmodified_since, if_match, if_none_match] = names;
+        let mut headers = vec![];
+        if let Some(time) = self.if_modified_since {
+            headers.push((modified_since, time.format(HTTP_DATE).to_string()));
+        }
+        if let Some(time) = self.if_unmodified_since {
+            headers.push((unmodified_since, time.format(HTTP_DATE).to_string()));
+        }
+        if let Some(ref tag) = self.if_match {
+            headers.push((if_match, tag.to_string()));
+        }
+        if let Some(ref tag) = self.if_none_match {
+            headers.push((if_none_match, tag.to_string()));
+        }
+        headers
+    }
+}
+
+/// Result of a request carrying `Preconditions`.
+#[derive(Debug)]
+pub enum Conditional<T> {
+    /// The preconditions held and the request was carried out.
+    Satisfied(T),
+    /// 304, the object matches `if_none_match` or was not modified since `if_modified_since`.
+    NotModified,
+    /// 412, the object fails `if_match` or was modified since `if_unmodified_since`.
+    PreconditionFailed,
+}
+
+impl<T> Conditional<T> {
+    /// Sort the result of a conditional request into its outcome.
+    pub(crate) fn from_result(result: Result<T>) -> Result<Self> {
+        match result {
+            Ok(val) => Ok(Conditional::Satisfied(val)),
+            Err(err) => match err.service_error().map(|err| err.status) {
+                Some(StatusCode::NOT_MODIFIED) => Ok(Conditional::NotModified),
+                Some(StatusCode::PRECONDITION_FAILED) => Ok(Conditional::PreconditionFailed),
+                _ => Err(err),
+            },
+        }
+    }
+    /// The result if the preconditions held.
+    pub fn satisfied(self) -> Option<T> {
+        match self {
+            Conditional::Satisfied(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn etag_test() {
+        let tag: ETag = "\"5B3C1A2E053D763E1B002CC607C5A0FE\"".parse().unwrap();
+        assert_eq!(tag.as_str(), "5B3C1A2E053D763E1B002CC607C5A0FE");
+        assert_eq!(tag, ETag::new("5B3C1A2E053D763E1B002CC607C5A0FE"));
+        assert_eq!(tag.to_string(), "\"5B3C1A2E053D763E1B002CC607C5A0FE\"");
+    }
+    #[test]
+    fn headers_test() {
+        let preconditions = Preconditions {
+            if_modified_since: Some("2020-10-07T14:47:53Z".parse().unwrap()),
+            if_none_match: Some(ETag::new("abc")),
+            ..Default::default()
+        };
+        assert_eq!(
+            preconditions.to_headers(),
+            vec![
+                (
+                    "if-modified-since",
+                    "Wed, 07 Oct 2020 14:47:53 GMT".to_owned()
+                ),
+                ("if-none-match", "\"abc\"".to_owned()),
+            ]
+        );
+        assert_eq!(
+            preconditions.to_copy_source_headers()[0].0,
+            "x-oss-copy-source-if-modified-since"
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use std::{fmt, sync::Arc};
+
+/// Transfer events reported to a `ProgressListener`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ProgressEvent {
+    /// The transfer is about to start, `total` is the number of bytes if known.
+    Started { total: Option<u64> },
+    /// `transferred` bytes out of `total` have been sent or received so far.
+    Transferred {
+        transferred: u64,
+        total: Option<u64>,
+    },
+    /// A part of a multipart transfer has been uploaded.
+    PartCompleted { part_number: u32, size: u64 },
+    /// The request is sent again, `attempt` counts from 1 for the first retry.
+    Retried { attempt: u32 },
+    /// Every byte has been transferred.
+    Completed { transferred: u64 },
+    /// The transfer was aborted by an error.
+    Failed { transferred: u64 },
+}
+
+/// Receives `ProgressEvent`s of a transfer.
+///
+/// Listeners are called from the task driving the transfer and should return quickly.
+pub trait ProgressListener: Send + Sync {
+    fn on_progress(&self, event: ProgressEvent);
+}
+
+impl<F> ProgressListener for F
+where
+    F: Fn(ProgressEvent) + Send + Sync,
+{
+    fn on_progress(&self, event: ProgressEvent) {
+        self(event)
+    }
+}
+
+/// A shareable handle to a `ProgressListener`, as attached to transfer options.
+#[derive(Clone)]
+pub struct Progress(Arc<dyn ProgressListener>);
+
+impl Progress {
+    pub fn new<L>(listener: L) -> Self
+    where
+        L: ProgressListener + 'static,
+    {
+        Self(Arc::new(listener))
+    }
+    pub(crate) fn emit(&self, event: ProgressEvent) {
+        self.0.on_progress(event)
+    }
+}
+
+impl<L> From<Arc<L>> for Progress
+where
+    L: ProgressListener + 'static,
+{
+    fn from(listener: Arc<L>) -> Self {
+        Self(listener)
+    }
+}
+
+impl fmt::Debug for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("<Progress>")
+    }
+}
+
+/// Two handles are equal when they point to the same listener.
+impl PartialEq for Progress {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    fmt,
+    ops::{Range, RangeFrom, RangeInclusive},
+    str::FromStr,
+};
+
+use super::{Error, Result};
+
+/// The bytes of an object to read, as sent in the `Range` header.
+///
+/// Build it from `a..b`, `a..=b` or `a..`, or with `ByteRange::last(n)` for the final `n` bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteRange {
+    /// Bytes `start` up to but excluding `end`
+    Bounded { start: u64, end: u64 },
+    /// Bytes from `start` to the end of the object
+    From { start: u64 },
+    /// The last `len` bytes of the object
+    Suffix { len: u64 },
+}
+
+impl ByteRange {
+    /// The last `len` bytes of the object.
+    pub fn last(len: u64) -> Self {
+        ByteRange::Suffix { len }
+    }
+    /// Value of the `Range` header, rejecting ranges that select no byte.
+    pub(crate) fn to_header(self) -> Result<String> {
+        match self {
+            ByteRange::Bounded { start, end } if start < end => {
+                Ok(format!("bytes={}-{}", start, end - 1))
+            }
+            ByteRange::From { start } => Ok(format!("bytes={}-", start)),
+            ByteRange::Suffix { len } if len > 0 => Ok(format!("bytes=-{}", len)),
+            _ => Err(Error::invalid_argument(format!(
+                "empty byte range {:?}",
+                self
+            ))),
+        }
+    }
+    /// Whether `content_range` is what OSS should return for this range.
+    ///
+    /// A range running past the object is cut at its end, any other short range is truncated.
+    pub(crate) fn is_satisfied_by(&self, content_range: &ContentRange) -> bool {
+        let end = content_range.end + 1;
+        let to_object_end = content_range.total == Some(end);
+        match *self {
+            ByteRange::Bounded {
+                start,
+                end: range_end,
+            } => {
+                content_range.start == start
+                    && (end == range_end || (to_object_end && end < range_end))
+            }
+            ByteRange::From { start } => content_range.start == start && to_object_end,
+            ByteRange::Suffix { len } => content_range.len() <= len && to_object_end,
+        }
+    }
+}
+
+impl From<Range<u64>> for ByteRange {
+    fn from(range: Range<u64>) -> Self {
+        ByteRange::Bounded {
+            start: range.start,
+            end: range.end,
+        }
+    }
+}
+impl From<RangeInclusive<u64>> for ByteRange {
+    fn from(range: RangeInclusive<u64>) -> Self {
+        ByteRange::Bounded {
+            start: *range.start(),
+            end: range.end().saturating_add(1),
+        }
+    }
+}
+impl From<RangeFrom<u64>> for ByteRange {
+    fn from(range: RangeFrom<u64>) -> Self {
+        ByteRange::From { start: range.start }
+    }
+}
+
+/// The bytes returned by a ranged read, parsed from the `Content-Range` header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ContentRange {
+    /// Offset of the first byte returned
+    pub start: u64,
+    /// Offset of the last byte returned, inclusive as in the header
+    pub end: u64,
+    /// Size of the whole object, `None` when reported as `*`
+    pub total: Option<u64>,
+}
+
+impl ContentRange {
+    /// Number of bytes returned.
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+    /// Always false, a `Content-Range` covers at least one byte.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+impl FromStr for ContentRange {
+    type Err = Error;
+
+    /// Parse `bytes <start>-<end>/<total>`.
+    fn from_str(s: &str) -> Result<Self> {
+        let malformed = || Error::range(format!("malformed Content-Range {:?}", s));
+        let spec = s.trim().strip_prefix("bytes ").ok_or_else(malformed)?;
+        let (range, total) = spec.split_once('/').ok_or_else(malformed)?;
+        let (start, end) = range.split_once('-').ok_or_else(malformed)?;
+        let start: u64 = start.parse().map_err(|_| malformed())?;
+        let end: u64 = end.parse().map_err(|_| malformed())?;
+        let total = match total {
+            "*" => None,
+            total => Some(total.parse::<u64>().map_err(|_| malformed())?),
+        };
+        if end < start || matches!(total, Some(total) if end >= total) {
+            return Err(malformed());
+        }
+        Ok(ContentRange { start, end, total })
+    }
+}
+
+impl fmt::Display for ContentRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "bytes {}-{}/", self.start, self.end)?;
+        match self.total {
+            Some(total) => write!(f, "{}", total),
+            None => f.write_str("*"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_range_header_test() {
+        assert_eq!(ByteRange::from(0..10).to_header().unwrap(), "bytes=0-9");
+        assert_eq!(ByteRange::from(5..=5).to_header().unwrap(), "bytes=5-5");
+        assert_eq!(ByteRange::from(100..).to_header().unwrap(), "bytes=100-");
+        assert_eq!(ByteRange::last(20).to_header().unwrap(), "bytes=-20");
+        assert!(ByteRange::from(10..10).to_header().is_err());
+        assert!(ByteRange::last(0).to_header().is_err());
+    }
+    #[test]
+    fn content_range_test() {
+        let range: ContentRange = "bytes 0-9/44".parse().unwrap();
+        assert_eq!(
+            range,
+            ContentRange {
+                start: 0,
+                end: 9,
+                total: Some(44)
+            }
+        );
+        assert_eq!(range.len(), 10);
+        assert_eq!(range.to_string(), "bytes 0-9/44");
+        assert_eq!("bytes 3-4/*".parse::<ContentRange>().unwrap().total, None);
+        assert!("bytes 9-0/44".parse::<ContentRange>().is_err());
+        assert!("bytes 0-44/44".parse::<ContentRange>().is_err());
+        assert!("bytes */44".parse::<ContentRange>().is_err());
+    }
+    #[test]
+    fn satisfied_test() {
+        let range: ContentRange = "bytes 40-43/44".parse().unwrap();
+        assert!(ByteRange::from(40..100).is_satisfied_by(&range));
+        assert!(ByteRange::from(40..).is_satisfied_by(&range));
+        assert!(ByteRange::last(4).is_satisfied_by(&range));
+        assert!(!ByteRange::from(0..10).is_satisfied_by(&range));
+
+        let range: ContentRange = "bytes 0-9/44".parse().unwrap();
+        assert!(ByteRange::from(0..10).is_satisfied_by(&range));
+        assert!(ByteRange::from(0..=9).is_satisfied_by(&range));
+        // Truncated short of the range and of the object.
+        assert!(!ByteRange::from(0..20).is_satisfied_by(&range));
+        assert!(!ByteRange::from(0..).is_satisfied_by(&range));
+        let range: ContentRange = "bytes 30-39/44".parse().unwrap();
+        assert!(!ByteRange::last(14).is_satisfied_by(&range));
+        assert!(!ByteRange::last(10).is_satisfied_by(&range));
+        // The end of the object is unknown.
+        let range: ContentRange = "bytes 40-43/*".parse().unwrap();
+        assert!(ByteRange::from(40..44).is_satisfied_by(&range));
+        assert!(!ByteRange::from(40..100).is_satisfied_by(&range));
+        assert!(!ByteRange::last(4).is_satisfied_by(&range));
+    }
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Deserializer};
+
+use super::xml::escape;
+
+/// Hotlink protection of a bucket, admitting requests by their Referer header.
+///
+/// Referers may contain the wildcards `*`, any number of characters, and `?`, a single
+/// character. Referers configured without a scheme, such as `www.example.com`, match any.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RefererConfiguration {
+    /// Admit requests without a Referer header, or with an empty one
+    pub allow_empty_referer: bool,
+    /// Drop the query string of the Referer before matching it
+    #[serde(default = "default_true")]
+    pub allow_truncate_query_string: bool,
+    /// Drop the path of the Referer before matching it, with `allow_truncate_query_string`
+    #[serde(default)]
+    pub truncate_path: bool,
+    /// Referers admitted, all when empty
+    #[serde(rename = "RefererList", default, deserialize_with = "referers")]
+    pub referer_list: Vec<String>,
+    /// Referers refused, even if `referer_list` admits them
+    #[serde(rename = "RefererBlacklist", default, deserialize_with = "referers")]
+    pub referer_blacklist: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Admits everything, as OSS does without a referer configuration.
+impl Default for RefererConfiguration {
+    fn default() -> Self {
+        Self {
+            allow_empty_referer: true,
+            allow_truncate_query_string: true,
+            truncate_path: false,
+            referer_list: vec![],
+            referer_blacklist: vec![],
+        }
+    }
+}
+
+fn referers<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Referers {
+        #[serde(rename = "Referer", default)]
+        referers: Vec<String>,
+    }
+    Ok(Referers::deserialize(deserializer)?.referers)
+}
+
+impl RefererConfiguration {
+    /// Whether a request with the Referer header `referer` would be admitted.
+    pub fn allows(&self, referer: Option<&str>) -> bool {
+        let referer = match referer.map(str::trim) {
+            Some(referer) if !referer.is_empty() => referer,
+            _ => return self.allow_empty_referer,
+        };
+        let mut referer = referer;
+        if self.allow_truncate_query_string {
+            referer = referer.split('?').next().unwrap_or(referer);
+            if self.truncate_path {
+                let host_start = referer.find("://").map_or(0, |idx| idx + 3);
+                if let Some(idx) = referer[host_start..].find('/') {
+                    referer = &referer[..host_start + idx];
+                }
+            }
+        }
+        let matches = |pattern: &String| {
+            if pattern.contains("://") {
+                wildcard_match(pattern, referer)
+            } else {
+                let without_scheme = referer
+                    .find("://")
+                    .map_or(referer, |idx| &referer[idx + 3..]);
+                wildcard_match(pattern, without_scheme)
+            }
+        };
+        if self.referer_blacklist.iter().any(matches) {
+            return false;
+        }
+        self.referer_list.is_empty() || self.referer_list.iter().any(matches)
+    }
+    /// Body of a PutBucketReferer request.
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = format!(
+            "<RefererConfiguration><AllowEmptyReferer>{}</AllowEmptyReferer>\
+             <AllowTruncateQueryString>{}</AllowTruncateQueryString>\
+             <TruncatePath>{}</TruncatePath>",
+            self.allow_empty_referer, self.allow_truncate_query_string, self.truncate_path
+        );
+        xml += "<RefererList>";
+        for referer in &self.referer_list {
+            xml += &format!("<Referer>{}</Referer>", escape(referer));
+        }
+        xml += "</RefererList><RefererBlacklist>";
+        for referer in &self.referer_blacklist {
+            xml += &format!("<Referer>{}</Referer>", escape(referer));
+        }
+        xml += "</RefererBlacklist></RefererConfiguration>";
+        xml
+    }
+}
+
+/// Whether `text` matches `pattern`, where `*` stands for any run of characters and `?` for a
+/// single one.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and of the text it was tried against, to backtrack to.
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn referer_configuration_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<RefererConfiguration>
+  <AllowEmptyReferer>false</AllowEmptyReferer>
+  <AllowTruncateQueryString>true</AllowTruncateQueryString>
+  <TruncatePath>true</TruncatePath>
+  <RefererList>
+    <Referer>http://www.aliyun.com</Referer>
+    <Referer>https://www.aliyun.com</Referer>
+    <Referer>http://www.*.com</Referer>
+    <Referer>https://www.?.aliyuncs.com</Referer>
+  </RefererList>
+  <RefererBlacklist>
+    <Referer>http://www.refuse.com</Referer>
+  </RefererBlacklist>
+</RefererConfiguration>"#;
+        let ret: RefererConfiguration = quick_xml::de::from_str(xml).unwrap();
+        assert!(!ret.allow_empty_referer);
+        assert!(ret.truncate_path);
+        assert_eq!(ret.referer_list.len(), 4);
+        assert_eq!(ret.referer_blacklist, vec!["http://www.refuse.com"]);
+
+        let parsed: RefererConfiguration = quick_xml::de::from_str(&ret.to_xml()).unwrap();
+        assert_eq!(parsed, ret);
+
+        let ret: RefererConfiguration = quick_xml::de::from_str(
+            "<RefererConfiguration><AllowEmptyReferer>true</AllowEmptyReferer>\
+             <RefererList /></RefererConfiguration>",
+        )
+        .unwrap();
+        assert_eq!(ret, RefererConfiguration::default());
+    }
+    #[test]
+    fn allows_test() {
+        let config = RefererConfiguration {
+            allow_empty_referer: false,
+            truncate_path: true,
+            referer_list: vec![
+                "https://www.aliyun.com".to_owned(),
+                "http://www.*.com".to_owned(),
+                "https://www.?.aliyuncs.com".to_owned(),
+                "example.org".to_owned(),
+            ],
+            referer_blacklist: vec!["http://www.refuse.com".to_owned()],
+            ..Default::default()
+        };
+        assert!(!config.allows(None));
+        assert!(!config.allows(Some("")));
+        assert!(config.allows(Some("https://www.aliyun.com/product/oss?spm=1")));
+        assert!(!config.allows(Some("http://www.aliyun.org")));
+        assert!(config.allows(Some("http://www.test.com/index.html")));
+        assert!(!config.allows(Some("http://www.refuse.com/")));
+        assert!(config.allows(Some("https://www.a.aliyuncs.com")));
+        assert!(!config.allows(Some("https://www.ab.aliyuncs.com")));
+        assert!(config.allows(Some("http://example.org/")));
+        assert!(config.allows(Some("https://example.org/")));
+
+        let config = RefererConfiguration {
+            truncate_path: false,
+            ..config
+        };
+        assert!(!config.allows(Some("https://www.aliyun.com/product/oss")));
+        assert!(RefererConfiguration::default().allows(None));
+    }
+    #[test]
+    fn wildcard_match_test() {
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a*b*c", "aXXbYYc"));
+        assert!(wildcard_match("a*c", "abcbc"));
+        assert!(!wildcard_match("a*c", "abcb"));
+        assert!(wildcard_match("a?c", "abc"));
+        assert!(!wildcard_match("a?c", "ac"));
+    }
+}
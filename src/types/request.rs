@@ -1,9 +1,12 @@
 use http_client::Params;
-use hyper::{header::HeaderName, HeaderMap, Method};
+use hyper::{
+    header::{HeaderName, HeaderValue},
+    HeaderMap, Method,
+};
 
 use crate::OSS_META_PREFIX;
 
-use super::{payload::Payload, Result, Schema};
+use super::{payload::Payload, Acl, Preconditions, Progress, Result, Schema, ServerSideEncryption};
 
 pub type Metas = std::collections::BTreeMap<String, String>;
 
@@ -23,6 +26,8 @@ pub(crate) struct Request<'a> {
     params: Params,
     /// The request headers
     headers: HeaderMap,
+    /// Listener told about retries
+    progress: Option<Progress>,
 }
 impl<'a> Request<'a> {
     pub(crate) fn new(
@@ -42,8 +47,32 @@ impl<'a> Request<'a> {
             payload,
             params: params.unwrap_or_default(),
             headers: headers.unwrap_or_default(),
+            progress: None,
         }
     }
+    /// A copy of the request to send again, `None` when the payload can't be replayed.
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        let payload = match &self.payload {
+            Some(payload) => Some(payload.try_clone()?),
+            None => None,
+        };
+        Some(Self {
+            method: self.method.clone(),
+            bucket: self.bucket,
+            object: self.object,
+            schema: self.schema,
+            payload,
+            params: self.params.clone(),
+            headers: self.headers.clone(),
+            progress: self.progress.clone(),
+        })
+    }
+    pub(crate) fn set_progress(&mut self, progress: Option<Progress>) {
+        self.progress = progress;
+    }
+    pub(crate) fn progress(&self) -> Option<&Progress> {
+        self.progress.as_ref()
+    }
     pub(crate) fn headers(&self) -> &HeaderMap {
         &self.headers
     }
@@ -66,23 +95,63 @@ impl<'a> Request<'a> {
         }
         Ok(())
     }
+    pub(crate) fn add_server_side_encryption(
+        &mut self,
+        encryption: Option<&ServerSideEncryption>,
+    ) -> Result<()> {
+        if let Some(encryption) = encryption {
+            for (key, val) in encryption.to_headers() {
+                self.headers.insert(key, val.parse()?);
+            }
+        }
+        Ok(())
+    }
+    /// Set the canned ACL header `name`, `x-oss-acl` or `x-oss-object-acl`.
+    pub(crate) fn add_acl(&mut self, name: &'static str, acl: Option<Acl>) {
+        if let Some(acl) = acl {
+            self.headers
+                .insert(name, HeaderValue::from_static(acl.as_str()));
+        }
+    }
+    pub(crate) fn add_preconditions(
+        &mut self,
+        preconditions: Option<&Preconditions>,
+    ) -> Result<()> {
+        if let Some(preconditions) = preconditions {
+            for (key, val) in preconditions.to_headers() {
+                self.headers.insert(key, val.parse()?);
+            }
+        }
+        Ok(())
+    }
+    pub(crate) fn add_copy_source_preconditions(
+        &mut self,
+        preconditions: Option<&Preconditions>,
+    ) -> Result<()> {
+        if let Some(preconditions) = preconditions {
+            for (key, val) in preconditions.to_copy_source_headers() {
+                self.headers.insert(key, val.parse()?);
+            }
+        }
+        Ok(())
+    }
     pub(crate) fn get_method(&self) -> &Method {
         &self.method
     }
-    // pub(crate) fn get_bucket(&self) -> Option<&str> {
-    //     self.bucket
-    // }
+    pub(crate) fn get_bucket(&self) -> Option<&str> {
+        self.bucket
+    }
     pub(crate) fn get_object(&self) -> Option<&str> {
         self.object
     }
     pub(crate) fn get_params(&self) -> &Params {
         &self.params
     }
-    pub(crate) fn content_length(&self) -> usize {
-        if let Some(payload) = &self.payload {
-            payload.len().unwrap_or(0)
-        } else {
-            0
+    /// Length of the payload, `None` when it is not known up front.
+    pub(crate) fn content_length(&self) -> Option<usize> {
+        match &self.payload {
+            Some(payload) => payload.len(),
+            None => Some(0),
         }
     }
     pub(crate) fn take_payload(&mut self) -> Payload {
@@ -1,9 +1,13 @@
-use futures::StreamExt;
+use bytes::{Bytes, BytesMut};
+use futures::{StreamExt, TryStreamExt};
 use http_client::HttpResponse;
-use hyper::{HeaderMap, StatusCode};
+use hyper::{header::CONTENT_LENGTH, HeaderMap, StatusCode};
+use serde::de::DeserializeOwned;
 use std::io::Error as IoError;
 
-use crate::ByteStream;
+use crate::{ByteStream, ObjectMetadata, ServerSideEncryption, ServiceError, OSS_HASH_CRC64};
+
+use super::Result;
 
 pub struct Response {
     /// Status code of HTTP Request
@@ -14,6 +18,55 @@ pub struct Response {
     pub headers: HeaderMap,
 }
 
+impl Response {
+    /// The object metadata carried by the response headers.
+    pub fn metadata(&self) -> ObjectMetadata {
+        ObjectMetadata::from_headers(&self.headers)
+    }
+    /// The server-side encryption of the object as reported by OSS.
+    pub fn server_side_encryption(&self) -> Option<ServerSideEncryption> {
+        ServerSideEncryption::from_headers(&self.headers)
+    }
+    /// The CRC64-ECMA of the object as reported by OSS.
+    pub(crate) fn crc64(&self) -> Option<u64> {
+        self.headers
+            .get(OSS_HASH_CRC64)
+            .and_then(|val| val.to_str().ok())
+            .and_then(|val| val.parse().ok())
+    }
+    /// Turn a non-2xx response into an error, parsing the error document in the body.
+    pub(crate) async fn error_for_status(self) -> Result<Self> {
+        if self.status.is_success() {
+            return Ok(self);
+        }
+        let (status, headers) = (self.status, self.headers.clone());
+        // A body that fails midway is still worth reporting the status for.
+        let body = self.into_bytes().await.unwrap_or_default();
+        Err(ServiceError::from_response(status, &headers, body).into())
+    }
+    /// Collect the whole body.
+    pub(crate) async fn into_bytes(self) -> Result<Bytes> {
+        let buf = self
+            .body
+            .try_fold(BytesMut::new(), |mut buf, chunk| async move {
+                buf.extend_from_slice(&chunk);
+                Ok(buf)
+            })
+            .await?;
+        Ok(buf.freeze())
+    }
+    /// Deserialize the XML document in the body.
+    pub(crate) async fn into_xml<T: DeserializeOwned>(self) -> Result<T> {
+        let bytes = self.into_bytes().await?;
+        Ok(quick_xml::de::from_reader(&bytes[..])?)
+    }
+    /// Deserialize the JSON document in the body.
+    pub(crate) async fn into_json<T: DeserializeOwned>(self) -> Result<T> {
+        let bytes = self.into_bytes().await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
 impl From<HttpResponse> for Response {
     fn from(http_response: HttpResponse) -> Self {
         let HttpResponse {
@@ -21,14 +74,22 @@ impl From<HttpResponse> for Response {
             headers,
             body,
         } = http_response;
-        let body = ByteStream::new(body.map(|try_chunk| {
+        let body = body.map(|try_chunk| {
             try_chunk.map(|c| c).map_err(|e| {
                 IoError::new(
                     std::io::ErrorKind::Other,
                     format!("Error obtaining chunk: {}", e),
                 )
             })
-        }));
+        });
+        let content_length = headers
+            .get(CONTENT_LENGTH)
+            .and_then(|val| val.to_str().ok())
+            .and_then(|val| val.parse().ok());
+        let body = match content_length {
+            Some(len) => ByteStream::new_with_size(body, len),
+            None => ByteStream::new(body),
+        };
         Self {
             status,
             headers,
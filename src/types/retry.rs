@@ -0,0 +1,130 @@
+use std::{fmt, sync::Arc, time::Duration};
+
+use rand::Rng;
+
+use super::Error;
+
+/// When and how often a failed request is sent again.
+///
+/// Each attempt is signed anew. Only requests whose payload can be replayed are retried, a
+/// `Payload::Stream` is sent once whatever the policy. Use `Payload::File` or a
+/// `PayloadSource` for streamed uploads that should be retried.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    classifier: Arc<dyn Fn(&Error) -> bool + Send + Sync>,
+}
+
+impl RetryPolicy {
+    /// Up to `max_attempts` attempts in total, with the default backoff and classifier.
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+    /// Send every request once.
+    pub fn none() -> Self {
+        Self::new(1)
+    }
+    /// Wait `base * 2^(n-1)` before the n-th retry, at most `cap`.
+    pub fn with_backoff(mut self, base: Duration, cap: Duration) -> Self {
+        self.base_delay = base;
+        self.max_delay = cap;
+        self
+    }
+    /// Wait a random duration up to the backoff instead of the backoff itself, spreading out
+    /// the retries of concurrent requests.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+    /// Decide which errors are retried, `Error::is_retryable` by default.
+    pub fn with_classifier<F>(mut self, classifier: F) -> Self
+    where
+        F: Fn(&Error) -> bool + Send + Sync + 'static,
+    {
+        self.classifier = Arc::new(classifier);
+        self
+    }
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+    /// Whether `err`, returned by attempt number `attempt`, is worth another attempt.
+    pub(crate) fn should_retry(&self, attempt: u32, err: &Error) -> bool {
+        attempt < self.max_attempts && (self.classifier)(err)
+    }
+    /// How long to wait after attempt number `attempt` failed.
+    pub(crate) fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        let delay = self
+            .base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+        if self.jitter && delay > Duration::from_secs(0) {
+            rand::thread_rng().gen_range(Duration::from_secs(0)..=delay)
+        } else {
+            delay
+        }
+    }
+}
+
+/// Three attempts, backing off from 200ms up to 20s with jitter.
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(20),
+            jitter: true,
+            classifier: Arc::new(Error::is_retryable),
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("base_delay", &self.base_delay)
+            .field("max_delay", &self.max_delay)
+            .field("jitter", &self.jitter)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_test() {
+        let policy = RetryPolicy::new(10)
+            .with_backoff(Duration::from_millis(100), Duration::from_secs(1))
+            .with_jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(64), Duration::from_secs(1));
+        let policy = policy.with_jitter(true);
+        for attempt in 1..8 {
+            assert!(policy.backoff(attempt) <= Duration::from_secs(1));
+        }
+    }
+    #[test]
+    fn should_retry_test() {
+        let err = Error::from(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        let policy = RetryPolicy::new(2);
+        assert!(policy.should_retry(1, &err));
+        assert!(!policy.should_retry(2, &err));
+        assert!(!RetryPolicy::none().should_retry(1, &err));
+        let policy = policy.with_classifier(|_| false);
+        assert!(!policy.should_retry(1, &err));
+    }
+}
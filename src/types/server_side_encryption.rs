@@ -0,0 +1,117 @@
+use hyper::HeaderMap;
+
+pub(crate) const OSS_SERVER_SIDE_ENCRYPTION: &str = "x-oss-server-side-encryption";
+pub(crate) const OSS_SERVER_SIDE_ENCRYPTION_KEY_ID: &str = "x-oss-server-side-encryption-key-id";
+pub(crate) const OSS_SERVER_SIDE_DATA_ENCRYPTION: &str = "x-oss-server-side-data-encryption";
+
+/// Server-side encryption applied by OSS to stored objects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ServerSideEncryption {
+    /// Keys managed by OSS, AES-256
+    Aes256,
+    /// Keys managed by OSS, SM4
+    Sm4,
+    /// Keys managed by KMS
+    Kms {
+        /// The customer master key, the default KMS key when `None`
+        key_id: Option<String>,
+        /// Algorithm encrypting the data, AES-256 when `None`
+        data_encryption: Option<DataEncryption>,
+    },
+}
+
+/// Data encryption algorithms selectable with KMS encryption.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataEncryption {
+    Sm4,
+}
+
+impl ServerSideEncryption {
+    /// KMS encryption with the default key and algorithm.
+    pub fn kms() -> Self {
+        ServerSideEncryption::Kms {
+            key_id: None,
+            data_encryption: None,
+        }
+    }
+    /// The request headers selecting this encryption.
+    pub(crate) fn to_headers(&self) -> Vec<(&'static str, String)> {
+        match self {
+            ServerSideEncryption::Aes256 => {
+                vec![(OSS_SERVER_SIDE_ENCRYPTION, "AES256".to_owned())]
+            }
+            ServerSideEncryption::Sm4 => vec![(OSS_SERVER_SIDE_ENCRYPTION, "SM4".to_owned())],
+            ServerSideEncryption::Kms {
+                key_id,
+                data_encryption,
+            } => {
+                let mut headers = vec![(OSS_SERVER_SIDE_ENCRYPTION, "KMS".to_owned())];
+                if let Some(key_id) = key_id {
+                    headers.push((OSS_SERVER_SIDE_ENCRYPTION_KEY_ID, key_id.to_owned()));
+                }
+                if let Some(DataEncryption::Sm4) = data_encryption {
+                    headers.push((OSS_SERVER_SIDE_DATA_ENCRYPTION, "SM4".to_owned()));
+                }
+                headers
+            }
+        }
+    }
+    /// The encryption reported in response headers, if any.
+    pub(crate) fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let header = |name: &str| headers.get(name).and_then(|val| val.to_str().ok());
+        Self::from_parts(
+            header(OSS_SERVER_SIDE_ENCRYPTION)?,
+            header(OSS_SERVER_SIDE_ENCRYPTION_KEY_ID),
+            header(OSS_SERVER_SIDE_DATA_ENCRYPTION),
+        )
+    }
+    /// The encryption named by an algorithm, KMS key ID and data encryption algorithm, `None`
+    /// for unknown algorithms and `None` itself.
+    pub(crate) fn from_parts(
+        algorithm: &str,
+        key_id: Option<&str>,
+        data_encryption: Option<&str>,
+    ) -> Option<Self> {
+        match algorithm {
+            "AES256" => Some(ServerSideEncryption::Aes256),
+            "SM4" => Some(ServerSideEncryption::Sm4),
+            "KMS" => Some(ServerSideEncryption::Kms {
+                key_id: key_id.filter(|id| !id.is_empty()).map(str::to_owned),
+                data_encryption: match data_encryption {
+                    Some("SM4") => Some(DataEncryption::Sm4),
+                    _ => None,
+                },
+            }),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        let encryptions = vec![
+            ServerSideEncryption::Aes256,
+            ServerSideEncryption::Sm4,
+            ServerSideEncryption::kms(),
+            ServerSideEncryption::Kms {
+                key_id: Some("9468da86-3509-4f8d-a61e-6eab1eac****".to_owned()),
+                data_encryption: Some(DataEncryption::Sm4),
+            },
+        ];
+        for encryption in encryptions {
+            let mut headers = HeaderMap::new();
+            for (key, val) in encryption.to_headers() {
+                headers.insert(key, val.parse().unwrap());
+            }
+            assert_eq!(
+                ServerSideEncryption::from_headers(&headers),
+                Some(encryption)
+            );
+        }
+        assert_eq!(ServerSideEncryption::from_headers(&HeaderMap::new()), None);
+    }
+}
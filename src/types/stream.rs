@@ -1,14 +1,24 @@
 use bytes::{BufMut, Bytes, BytesMut};
-use futures::{future, stream, Stream, StreamExt};
+use futures::{future, stream, Stream, StreamExt, TryStreamExt};
 use hyper::Body;
 use pin_project::pin_project;
 use std::{
     fmt,
+    future::Future,
     io::{self, Error as IoError},
     pin::Pin,
+    sync::{Arc, Mutex},
     task::{Context, Poll},
+    time::Duration,
 };
-use tokio::io::{AsyncRead, ReadBuf};
+use tokio::{
+    io::{AsyncRead, ReadBuf},
+    time::{sleep, Sleep},
+};
+
+use crate::{Crc64, IntegrityError, Progress, ProgressEvent, RateLimiter};
+
+type BoxedStream = Pin<Box<dyn Stream<Item = Result<Bytes, io::Error>> + Send + 'static>>;
 
 /// Stream of bytes.
 #[pin_project]
@@ -19,12 +29,15 @@ pub struct ByteStream {
 }
 impl ByteStream {
     /// Create a new `ByteStream` by wrapping a `futures` stream.
+    ///
+    /// The length of the stream is unknown, the size hint of `stream` counts chunks rather
+    /// than bytes. Use `new_with_size` when the length in bytes is known.
     pub fn new<S>(stream: S) -> ByteStream
     where
         S: Stream<Item = Result<Bytes, io::Error>> + Send + 'static,
     {
         ByteStream {
-            size_hint: stream.size_hint(),
+            size_hint: (0, None),
             inner: Box::pin(stream),
         }
     }
@@ -44,6 +57,70 @@ impl ByteStream {
         self.size_hint
     }
 
+    /// Transform every chunk with `f`, keeping the size hint.
+    pub(crate) fn map_chunks<F>(self, f: F) -> ByteStream
+    where
+        F: FnMut(Bytes) -> Bytes + Send + 'static,
+    {
+        ByteStream {
+            size_hint: self.size_hint,
+            inner: Box::pin(self.inner.map_ok(f)),
+        }
+    }
+
+    /// Feed every chunk passing through into `crc`.
+    pub(crate) fn hash_crc64(self, crc: Arc<Mutex<Crc64>>) -> ByteStream {
+        ByteStream {
+            size_hint: self.size_hint,
+            inner: Box::pin(
+                self.inner
+                    .inspect_ok(move |chunk| crc.lock().unwrap().update(chunk)),
+            ),
+        }
+    }
+
+    /// Check the CRC64-ECMA of the whole stream against `expected` once it ends.
+    ///
+    /// On mismatch the final item is an `InvalidData` error wrapping an `IntegrityError`.
+    pub(crate) fn verify_crc64(self, expected: u64) -> ByteStream {
+        ByteStream {
+            size_hint: self.size_hint,
+            inner: Box::pin(Crc64Verify {
+                inner: self.inner,
+                crc: Crc64::new(),
+                expected,
+                finished: false,
+            }),
+        }
+    }
+
+    /// Report the bytes passing through to `progress`, `total` being the expected length.
+    pub(crate) fn with_progress(self, progress: Progress, total: Option<u64>) -> ByteStream {
+        progress.emit(ProgressEvent::Started { total });
+        ByteStream {
+            size_hint: self.size_hint,
+            inner: Box::pin(ProgressStream {
+                inner: self.inner,
+                progress,
+                transferred: 0,
+                total,
+            }),
+        }
+    }
+
+    /// Hold back chunks so that the streams sharing `limiter` stay within its rate.
+    pub fn throttle(self, limiter: RateLimiter) -> ByteStream {
+        ByteStream {
+            size_hint: self.size_hint,
+            inner: Box::pin(ThrottledStream {
+                inner: self.inner,
+                limiter,
+                delay: None,
+                pending: None,
+            }),
+        }
+    }
+
     /// Return an implementation of `AsyncRead` that uses async i/o to consume the stream.
     pub fn into_async_read(self) -> impl AsyncRead + Send {
         ImplAsyncRead::new(self.inner)
@@ -91,6 +168,119 @@ impl Stream for ByteStream {
     }
 }
 
+#[pin_project]
+struct Crc64Verify {
+    #[pin]
+    inner: BoxedStream,
+    crc: Crc64,
+    expected: u64,
+    finished: bool,
+}
+
+impl Stream for Crc64Verify {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if *this.finished {
+            return Poll::Ready(None);
+        }
+        match futures::ready!(this.inner.poll_next(cx)) {
+            Some(Ok(bytes)) => {
+                this.crc.update(&bytes);
+                Poll::Ready(Some(Ok(bytes)))
+            }
+            Some(Err(e)) => Poll::Ready(Some(Err(e))),
+            None => {
+                *this.finished = true;
+                let actual = this.crc.value();
+                if actual == *this.expected {
+                    Poll::Ready(None)
+                } else {
+                    let err = IntegrityError {
+                        expected: *this.expected,
+                        actual,
+                    };
+                    Poll::Ready(Some(Err(IoError::new(io::ErrorKind::InvalidData, err))))
+                }
+            }
+        }
+    }
+}
+
+#[pin_project]
+struct ProgressStream {
+    #[pin]
+    inner: BoxedStream,
+    progress: Progress,
+    transferred: u64,
+    total: Option<u64>,
+}
+
+impl Stream for ProgressStream {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let item = futures::ready!(this.inner.poll_next(cx));
+        let event = match &item {
+            Some(Ok(bytes)) => {
+                *this.transferred += bytes.len() as u64;
+                ProgressEvent::Transferred {
+                    transferred: *this.transferred,
+                    total: *this.total,
+                }
+            }
+            Some(Err(_)) => ProgressEvent::Failed {
+                transferred: *this.transferred,
+            },
+            None => ProgressEvent::Completed {
+                transferred: *this.transferred,
+            },
+        };
+        this.progress.emit(event);
+        Poll::Ready(item)
+    }
+}
+
+#[pin_project]
+struct ThrottledStream {
+    #[pin]
+    inner: BoxedStream,
+    limiter: RateLimiter,
+    delay: Option<Pin<Box<Sleep>>>,
+    pending: Option<Bytes>,
+}
+
+impl Stream for ThrottledStream {
+    type Item = Result<Bytes, io::Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        if let Some(delay) = this.delay.as_mut() {
+            futures::ready!(delay.as_mut().poll(cx));
+            *this.delay = None;
+            return Poll::Ready(this.pending.take().map(Ok));
+        }
+        match futures::ready!(this.inner.poll_next(cx)) {
+            Some(Ok(bytes)) => {
+                let wait = this.limiter.reserve(bytes.len());
+                if wait == Duration::from_secs(0) {
+                    return Poll::Ready(Some(Ok(bytes)));
+                }
+                let mut delay = Box::pin(sleep(wait));
+                if delay.as_mut().poll(cx).is_ready() {
+                    return Poll::Ready(Some(Ok(bytes)));
+                }
+                *this.delay = Some(delay);
+                *this.pending = Some(bytes);
+                Poll::Pending
+            }
+            item => Poll::Ready(item),
+        }
+    }
+}
+
 #[pin_project]
 struct ImplAsyncRead {
     buffer: BytesMut,
@@ -229,3 +419,90 @@ async fn test_new_with_size_read() {
     assert_eq!(&buf[..3], b"Doc");
     assert_eq!(async_read.read(&mut buf).await.unwrap(), 0);
 }
+
+#[tokio::test]
+async fn test_verify_crc64() {
+    use tokio::io::AsyncReadExt;
+
+    let expected = Crc64::checksum(b"ShimoDoc");
+    let chunks = || {
+        stream::iter(vec![
+            Ok(Bytes::from_static(b"Shimo")),
+            Ok(Bytes::from_static(b"Doc")),
+        ])
+    };
+    let mut buf = String::new();
+    let mut async_read = ByteStream::new(chunks())
+        .verify_crc64(expected)
+        .into_async_read();
+    async_read.read_to_string(&mut buf).await.unwrap();
+    assert_eq!(buf, "ShimoDoc");
+
+    let err = ByteStream::new(chunks())
+        .verify_crc64(expected + 1)
+        .into_async_read()
+        .read_to_string(&mut buf)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+#[tokio::test]
+async fn test_with_progress() {
+    use tokio::io::AsyncReadExt;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorder = events.clone();
+    let progress = Progress::new(move |event| recorder.lock().unwrap().push(event));
+
+    let chunks = vec![
+        Ok(Bytes::from_static(b"Shimo")),
+        Ok(Bytes::from_static(b"Doc")),
+    ];
+    let mut buf = String::new();
+    ByteStream::new(stream::iter(chunks))
+        .with_progress(progress, Some(8))
+        .into_async_read()
+        .read_to_string(&mut buf)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        *events.lock().unwrap(),
+        vec![
+            ProgressEvent::Started { total: Some(8) },
+            ProgressEvent::Transferred {
+                transferred: 5,
+                total: Some(8)
+            },
+            ProgressEvent::Transferred {
+                transferred: 8,
+                total: Some(8)
+            },
+            ProgressEvent::Completed { transferred: 8 },
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_throttle() {
+    use std::time::Instant;
+    use tokio::io::AsyncReadExt;
+
+    let chunks = vec![
+        Ok(Bytes::from_static(b"Shimo")),
+        Ok(Bytes::from_static(b"Doc")),
+    ];
+    // The first chunk drains the bucket, the second waits for 3 bytes at 30B/s.
+    let limiter = RateLimiter::with_burst(30, 5);
+    let start = Instant::now();
+    let mut buf = String::new();
+    ByteStream::new(stream::iter(chunks))
+        .throttle(limiter)
+        .into_async_read()
+        .read_to_string(&mut buf)
+        .await
+        .unwrap();
+    assert_eq!(buf, "ShimoDoc");
+    assert!(start.elapsed() >= Duration::from_millis(90));
+}
@@ -0,0 +1,145 @@
+use std::{
+    error::Error as StdError,
+    fmt::{self, Display, Formatter},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// Token bucket limiting the throughput of every transfer sharing it.
+///
+/// Clones share the same bucket, so one limiter can cap the total bandwidth of many concurrent
+/// transfers. Attach it with `ByteStream::throttle` or the `rate_limiter` transfer options.
+#[derive(Clone)]
+pub struct RateLimiter {
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+struct Bucket {
+    /// Bytes added per second
+    rate: f64,
+    /// Maximum number of bytes that may be sent in a burst
+    capacity: f64,
+    /// Available bytes, negative while transfers are waiting
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    /// Limit to `bytes_per_second`, allowing bursts of up to one second worth of data.
+    pub fn new(bytes_per_second: u64) -> Self {
+        Self::with_burst(bytes_per_second, bytes_per_second)
+    }
+    /// Limit to `bytes_per_second`, allowing bursts of up to `burst` bytes.
+    pub fn with_burst(bytes_per_second: u64, burst: u64) -> Self {
+        let rate = bytes_per_second.max(1) as f64;
+        let capacity = burst.max(1) as f64;
+        Self {
+            bucket: Arc::new(Mutex::new(Bucket {
+                rate,
+                capacity,
+                tokens: capacity,
+                last_refill: Instant::now(),
+            })),
+        }
+    }
+    /// Take `bytes` from the bucket, returning how long to wait before sending them.
+    pub(crate) fn reserve(&self, bytes: usize) -> Duration {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * bucket.rate).min(bucket.capacity);
+        bucket.last_refill = now;
+        bucket.tokens -= bytes as f64;
+        if bucket.tokens >= 0.0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / bucket.rate)
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let bucket = self.bucket.lock().unwrap();
+        write!(f, "<RateLimiter rate={}B/s>", bucket.rate)
+    }
+}
+
+/// Two limiters are equal when they share the same bucket.
+impl PartialEq for RateLimiter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.bucket, &other.bucket)
+    }
+}
+
+/// Server-side bandwidth limit of a single request, sent as `x-oss-traffic-limit`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TrafficLimit(u64);
+
+impl TrafficLimit {
+    /// Lowest limit accepted by OSS, 100 KB/s in bits per second.
+    pub const MIN: u64 = 819_200;
+    /// Highest limit accepted by OSS, 100 MB/s in bits per second.
+    pub const MAX: u64 = 838_860_800;
+
+    pub fn new(bits_per_second: u64) -> Result<Self, TrafficLimitError> {
+        if (Self::MIN..=Self::MAX).contains(&bits_per_second) {
+            Ok(Self(bits_per_second))
+        } else {
+            Err(TrafficLimitError::new(bits_per_second))
+        }
+    }
+    pub fn bits_per_second(&self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct TrafficLimitError {
+    message: String,
+}
+impl TrafficLimitError {
+    pub fn new(bits_per_second: u64) -> Self {
+        TrafficLimitError {
+            message: format!(
+                "Traffic limit {} bit/s out of range {}..={}",
+                bits_per_second,
+                TrafficLimit::MIN,
+                TrafficLimit::MAX
+            ),
+        }
+    }
+}
+impl StdError for TrafficLimitError {}
+impl Display for TrafficLimitError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reserve_test() {
+        let limiter = RateLimiter::new(100);
+        assert_eq!(limiter.reserve(100), Duration::from_secs(0));
+        let wait = limiter.reserve(50);
+        assert!(wait > Duration::from_millis(400) && wait <= Duration::from_millis(500));
+        // Clones draw from the same bucket.
+        let wait = limiter.clone().reserve(50);
+        assert!(wait > Duration::from_millis(900) && wait <= Duration::from_secs(1));
+    }
+    #[test]
+    fn traffic_limit_test() {
+        assert!(TrafficLimit::new(TrafficLimit::MIN - 1).is_err());
+        assert!(TrafficLimit::new(TrafficLimit::MAX + 1).is_err());
+        assert_eq!(
+            TrafficLimit::new(TrafficLimit::MIN)
+                .unwrap()
+                .bits_per_second(),
+            819_200
+        );
+    }
+}
@@ -0,0 +1,526 @@
+use std::convert::TryFrom;
+
+use serde::{de, Deserialize, Deserializer};
+
+use super::xml::escape;
+
+/// Static website hosting of a bucket.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct WebsiteConfiguration {
+    pub index_document: Option<IndexDocument>,
+    pub error_document: Option<ErrorDocument>,
+    /// Applied in ascending order of `rule_number`, the first matching rule wins
+    #[serde(default, deserialize_with = "routing_rules")]
+    pub routing_rules: Vec<RoutingRule>,
+}
+
+/// The default page served for requests on the root or a subdirectory.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct IndexDocument {
+    /// Name of the index page, such as `index.html`
+    pub suffix: String,
+    /// Serve the index page of subdirectories, not only of the root
+    #[serde(default)]
+    pub support_sub_dir: bool,
+    /// What happens on a request for `dir` when `dir` does not exist but `dir/` does
+    #[serde(rename = "Type")]
+    pub sub_dir_type: Option<SubDirType>,
+}
+
+/// Handling of subdirectory requests without a trailing slash, with `support_sub_dir`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SubDirType {
+    /// Redirect to `dir/`
+    Redirect,
+    /// Answer 404 Not Found, serving the error document
+    NotFound,
+    /// Serve `dir/` + the index page
+    Index,
+}
+
+impl SubDirType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SubDirType::Redirect => "0",
+            SubDirType::NotFound => "1",
+            SubDirType::Index => "2",
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for SubDirType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match String::deserialize(deserializer)?.as_str() {
+            "0" => Ok(SubDirType::Redirect),
+            "1" => Ok(SubDirType::NotFound),
+            "2" => Ok(SubDirType::Index),
+            other => Err(de::Error::custom(format!(
+                "unknown index document type {:?}",
+                other
+            ))),
+        }
+    }
+}
+
+/// The page served when an object is not found.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ErrorDocument {
+    pub key: String,
+    /// Status code the page is served with, 404 by default
+    pub http_status: Option<u16>,
+}
+
+/// A rule redirecting the requests matching its condition.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RoutingRule {
+    /// Order of the rule, from 1
+    pub rule_number: u32,
+    #[serde(default)]
+    pub condition: RoutingCondition,
+    pub redirect: Redirect,
+}
+
+/// The requests a `RoutingRule` applies to, all of the conditions set have to hold.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct RoutingCondition {
+    pub key_prefix_equals: Option<String>,
+    pub key_suffix_equals: Option<String>,
+    /// Only requests that would otherwise fail with this status code, such as 404
+    pub http_error_code_returned_equals: Option<u16>,
+    /// Request headers that have to be present with the given values
+    #[serde(rename = "IncludeHeader", default, deserialize_with = "key_values")]
+    pub include_headers: Vec<(String, String)>,
+}
+
+/// Where a `RoutingRule` sends matching requests.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Redirect {
+    /// Fetch the object from an origin server, storing it in the bucket
+    Mirror(MirrorRedirect),
+    /// Answer with a 3xx redirect to another host
+    External(ExternalRedirect),
+    /// Answer with a 3xx redirect through Alibaba Cloud CDN
+    AliCdn(ExternalRedirect),
+}
+
+/// How the target of a redirect is rewritten.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KeyRewrite {
+    /// Keep the query string of the request
+    pub pass_query_string: bool,
+    /// Replace the key, `${key}` standing for the original key
+    pub replace_key_with: Option<String>,
+    /// Replace the `key_prefix_equals` of the condition, with `enable_replace_prefix`
+    pub replace_key_prefix_with: Option<String>,
+    pub enable_replace_prefix: bool,
+}
+
+/// A 3xx redirect.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExternalRedirect {
+    /// `http` or `https`, that of the request when `None`
+    pub protocol: Option<String>,
+    /// The host redirected to, that of the request when `None`
+    pub host_name: Option<String>,
+    /// 301, 302 or 307, 302 when `None`
+    pub http_redirect_code: Option<u16>,
+    pub rewrite: KeyRewrite,
+}
+
+/// Back-to-origin fetching of missing objects.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MirrorRedirect {
+    /// The origin, such as `https://example.com/`
+    pub mirror_url: String,
+    /// Pass the query string of the request on to the origin
+    pub mirror_pass_query_string: bool,
+    /// Follow redirects returned by the origin
+    pub mirror_follow_redirect: bool,
+    /// Check the Content-MD5 returned by the origin
+    pub mirror_check_md5: bool,
+    pub mirror_headers: MirrorHeaders,
+    pub rewrite: KeyRewrite,
+}
+
+/// The request headers passed on to the origin of a mirror.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct MirrorHeaders {
+    /// Pass all headers but a few reserved ones
+    #[serde(default)]
+    pub pass_all: bool,
+    #[serde(default)]
+    pub pass: Vec<String>,
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Headers set on the request to the origin, whatever the client sent
+    #[serde(default, deserialize_with = "key_values")]
+    pub set: Vec<(String, String)>,
+}
+
+fn routing_rules<'de, D>(deserializer: D) -> Result<Vec<RoutingRule>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct RoutingRules {
+        #[serde(rename = "RoutingRule", default)]
+        rules: Vec<RoutingRule>,
+    }
+    Ok(RoutingRules::deserialize(deserializer)?.rules)
+}
+
+/// `<Key>` and `<Equals>` or `<Value>` pairs.
+fn key_values<'de, D>(deserializer: D) -> Result<Vec<(String, String)>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(rename_all = "PascalCase")]
+    struct KeyValue {
+        key: String,
+        #[serde(alias = "Equals", default)]
+        value: String,
+    }
+    Ok(Vec::<KeyValue>::deserialize(deserializer)?
+        .into_iter()
+        .map(|kv| (kv.key, kv.value))
+        .collect())
+}
+
+/// The `<Redirect>` element as sent by OSS, before it is sorted by type.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct RedirectDocument {
+    redirect_type: String,
+    protocol: Option<String>,
+    host_name: Option<String>,
+    http_redirect_code: Option<u16>,
+    #[serde(default)]
+    pass_query_string: bool,
+    replace_key_with: Option<String>,
+    replace_key_prefix_with: Option<String>,
+    #[serde(default)]
+    enable_replace_prefix: bool,
+    #[serde(rename = "MirrorURL")]
+    mirror_url: Option<String>,
+    #[serde(default)]
+    mirror_pass_query_string: bool,
+    #[serde(default)]
+    mirror_follow_redirect: bool,
+    #[serde(default)]
+    mirror_check_md5: bool,
+    #[serde(default)]
+    mirror_headers: MirrorHeaders,
+}
+
+impl TryFrom<RedirectDocument> for Redirect {
+    type Error = String;
+
+    fn try_from(doc: RedirectDocument) -> Result<Self, Self::Error> {
+        let rewrite = KeyRewrite {
+            pass_query_string: doc.pass_query_string,
+            replace_key_with: doc.replace_key_with,
+            replace_key_prefix_with: doc.replace_key_prefix_with,
+            enable_replace_prefix: doc.enable_replace_prefix,
+        };
+        let external = ExternalRedirect {
+            protocol: doc.protocol,
+            host_name: doc.host_name,
+            http_redirect_code: doc.http_redirect_code,
+            rewrite: rewrite.clone(),
+        };
+        match doc.redirect_type.as_str() {
+            "Mirror" => Ok(Redirect::Mirror(MirrorRedirect {
+                mirror_url: doc
+                    .mirror_url
+                    .ok_or_else(|| "mirror redirect without MirrorURL".to_owned())?,
+                mirror_pass_query_string: doc.mirror_pass_query_string,
+                mirror_follow_redirect: doc.mirror_follow_redirect,
+                mirror_check_md5: doc.mirror_check_md5,
+                mirror_headers: doc.mirror_headers,
+                rewrite,
+            })),
+            "External" => Ok(Redirect::External(external)),
+            "AliCDN" => Ok(Redirect::AliCdn(external)),
+            other => Err(format!("unknown redirect type {:?}", other)),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Redirect {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Self::try_from(RedirectDocument::deserialize(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+impl KeyRewrite {
+    fn to_xml(&self) -> String {
+        let mut xml = format!(
+            "<PassQueryString>{}</PassQueryString>",
+            self.pass_query_string
+        );
+        if let Some(ref key) = self.replace_key_with {
+            xml += &format!("<ReplaceKeyWith>{}</ReplaceKeyWith>", escape(key));
+        }
+        if let Some(ref prefix) = self.replace_key_prefix_with {
+            xml += &format!(
+                "<ReplaceKeyPrefixWith>{}</ReplaceKeyPrefixWith>",
+                escape(prefix)
+            );
+        }
+        if self.enable_replace_prefix {
+            xml += "<EnableReplacePrefix>true</EnableReplacePrefix>";
+        }
+        xml
+    }
+}
+
+impl ExternalRedirect {
+    fn to_xml(&self, redirect_type: &str) -> String {
+        let mut xml = format!("<RedirectType>{}</RedirectType>", redirect_type);
+        if let Some(ref protocol) = self.protocol {
+            xml += &format!("<Protocol>{}</Protocol>", escape(protocol));
+        }
+        if let Some(ref host_name) = self.host_name {
+            xml += &format!("<HostName>{}</HostName>", escape(host_name));
+        }
+        if let Some(code) = self.http_redirect_code {
+            xml += &format!("<HttpRedirectCode>{}</HttpRedirectCode>", code);
+        }
+        xml += &self.rewrite.to_xml();
+        xml
+    }
+}
+
+impl MirrorRedirect {
+    fn to_xml(&self) -> String {
+        let mut xml = String::from("<RedirectType>Mirror</RedirectType>");
+        xml += &format!("<MirrorURL>{}</MirrorURL>", escape(&self.mirror_url));
+        xml += &format!(
+            "<MirrorPassQueryString>{}</MirrorPassQueryString>\
+             <MirrorFollowRedirect>{}</MirrorFollowRedirect>\
+             <MirrorCheckMd5>{}</MirrorCheckMd5>",
+            self.mirror_pass_query_string, self.mirror_follow_redirect, self.mirror_check_md5
+        );
+        let headers = &self.mirror_headers;
+        xml += &format!("<MirrorHeaders><PassAll>{}</PassAll>", headers.pass_all);
+        for name in &headers.pass {
+            xml += &format!("<Pass>{}</Pass>", escape(name));
+        }
+        for name in &headers.remove {
+            xml += &format!("<Remove>{}</Remove>", escape(name));
+        }
+        for (key, value) in &headers.set {
+            xml += &format!(
+                "<Set><Key>{}</Key><Value>{}</Value></Set>",
+                escape(key),
+                escape(value)
+            );
+        }
+        xml += "</MirrorHeaders>";
+        xml += &self.rewrite.to_xml();
+        xml
+    }
+}
+
+impl WebsiteConfiguration {
+    /// Body of a PutBucketWebsite request.
+    pub(crate) fn to_xml(&self) -> String {
+        let mut xml = String::from("<WebsiteConfiguration>");
+        if let Some(ref index) = self.index_document {
+            xml += &format!(
+                "<IndexDocument><Suffix>{}</Suffix><SupportSubDir>{}</SupportSubDir>",
+                escape(&index.suffix),
+                index.support_sub_dir
+            );
+            if let Some(sub_dir_type) = index.sub_dir_type {
+                xml += &format!("<Type>{}</Type>", sub_dir_type.as_str());
+            }
+            xml += "</IndexDocument>";
+        }
+        if let Some(ref error) = self.error_document {
+            xml += &format!("<ErrorDocument><Key>{}</Key>", escape(&error.key));
+            if let Some(status) = error.http_status {
+                xml += &format!("<HttpStatus>{}</HttpStatus>", status);
+            }
+            xml += "</ErrorDocument>";
+        }
+        if !self.routing_rules.is_empty() {
+            xml += "<RoutingRules>";
+            for rule in &self.routing_rules {
+                xml += &format!("<RoutingRule><RuleNumber>{}</RuleNumber>", rule.rule_number);
+                xml += &rule.condition.to_xml();
+                xml += "<Redirect>";
+                xml += &match rule.redirect {
+                    Redirect::Mirror(ref mirror) => mirror.to_xml(),
+                    Redirect::External(ref external) => external.to_xml("External"),
+                    Redirect::AliCdn(ref external) => external.to_xml("AliCDN"),
+                };
+                xml += "</Redirect></RoutingRule>";
+            }
+            xml += "</RoutingRules>";
+        }
+        xml += "</WebsiteConfiguration>";
+        xml
+    }
+}
+
+impl RoutingCondition {
+    fn to_xml(&self) -> String {
+        let mut xml = String::from("<Condition>");
+        if let Some(ref prefix) = self.key_prefix_equals {
+            xml += &format!("<KeyPrefixEquals>{}</KeyPrefixEquals>", escape(prefix));
+        }
+        if let Some(ref suffix) = self.key_suffix_equals {
+            xml += &format!("<KeySuffixEquals>{}</KeySuffixEquals>", escape(suffix));
+        }
+        if let Some(code) = self.http_error_code_returned_equals {
+            xml += &format!(
+                "<HttpErrorCodeReturnedEquals>{}</HttpErrorCodeReturnedEquals>",
+                code
+            );
+        }
+        for (key, value) in &self.include_headers {
+            xml += &format!(
+                "<IncludeHeader><Key>{}</Key><Equals>{}</Equals></IncludeHeader>",
+                escape(key),
+                escape(value)
+            );
+        }
+        xml += "</Condition>";
+        xml
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn website_configuration_test() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<WebsiteConfiguration>
+  <IndexDocument>
+    <Suffix>index.html</Suffix>
+    <SupportSubDir>true</SupportSubDir>
+    <Type>0</Type>
+  </IndexDocument>
+  <ErrorDocument>
+    <Key>error.html</Key>
+    <HttpStatus>404</HttpStatus>
+  </ErrorDocument>
+  <RoutingRules>
+    <RoutingRule>
+      <RuleNumber>1</RuleNumber>
+      <Condition>
+        <KeyPrefixEquals>abc/</KeyPrefixEquals>
+        <HttpErrorCodeReturnedEquals>404</HttpErrorCodeReturnedEquals>
+      </Condition>
+      <Redirect>
+        <RedirectType>Mirror</RedirectType>
+        <PassQueryString>true</PassQueryString>
+        <MirrorURL>http://example.com/</MirrorURL>
+        <MirrorPassQueryString>true</MirrorPassQueryString>
+        <MirrorFollowRedirect>true</MirrorFollowRedirect>
+        <MirrorCheckMd5>false</MirrorCheckMd5>
+        <MirrorHeaders>
+          <PassAll>true</PassAll>
+          <Pass>myheader-key1</Pass>
+          <Pass>myheader-key2</Pass>
+          <Remove>myheader-key3</Remove>
+          <Set>
+            <Key>myheader-key5</Key>
+            <Value>myheader-value5</Value>
+          </Set>
+        </MirrorHeaders>
+      </Redirect>
+    </RoutingRule>
+    <RoutingRule>
+      <RuleNumber>2</RuleNumber>
+      <Condition>
+        <KeyPrefixEquals>abc/</KeyPrefixEquals>
+        <HttpErrorCodeReturnedEquals>404</HttpErrorCodeReturnedEquals>
+        <IncludeHeader>
+          <Key>host</Key>
+          <Equals>test.oss-cn-beijing-internal.aliyuncs.com</Equals>
+        </IncludeHeader>
+      </Condition>
+      <Redirect>
+        <RedirectType>AliCDN</RedirectType>
+        <Protocol>http</Protocol>
+        <HostName>example.com</HostName>
+        <PassQueryString>false</PassQueryString>
+        <ReplaceKeyWith>prefix/${key}.suffix</ReplaceKeyWith>
+        <HttpRedirectCode>301</HttpRedirectCode>
+      </Redirect>
+    </RoutingRule>
+    <RoutingRule>
+      <Condition>
+        <HttpErrorCodeReturnedEquals>404</HttpErrorCodeReturnedEquals>
+      </Condition>
+      <RuleNumber>3</RuleNumber>
+      <Redirect>
+        <ReplaceKeyWith>prefix/${key}</ReplaceKeyWith>
+        <HttpRedirectCode>302</HttpRedirectCode>
+        <EnableReplacePrefix>false</EnableReplacePrefix>
+        <PassQueryString>false</PassQueryString>
+        <Protocol>http</Protocol>
+        <HostName>example.com</HostName>
+        <RedirectType>External</RedirectType>
+      </Redirect>
+    </RoutingRule>
+  </RoutingRules>
+</WebsiteConfiguration>"#;
+        let ret: WebsiteConfiguration = quick_xml::de::from_str(xml).unwrap();
+        let index = ret.index_document.as_ref().unwrap();
+        assert!(index.support_sub_dir);
+        assert_eq!(index.sub_dir_type, Some(SubDirType::Redirect));
+        assert_eq!(ret.error_document.as_ref().unwrap().http_status, Some(404));
+        assert_eq!(ret.routing_rules.len(), 3);
+        match ret.routing_rules[0].redirect {
+            Redirect::Mirror(ref mirror) => {
+                assert_eq!(mirror.mirror_url, "http://example.com/");
+                assert!(mirror.rewrite.pass_query_string);
+                assert_eq!(mirror.mirror_headers.pass.len(), 2);
+                assert_eq!(
+                    mirror.mirror_headers.set,
+                    vec![("myheader-key5".to_owned(), "myheader-value5".to_owned())]
+                );
+            }
+            ref other => panic!("unexpected redirect {:?}", other),
+        }
+        assert_eq!(
+            ret.routing_rules[1].condition.include_headers,
+            vec![(
+                "host".to_owned(),
+                "test.oss-cn-beijing-internal.aliyuncs.com".to_owned()
+            )]
+        );
+        match ret.routing_rules[1].redirect {
+            Redirect::AliCdn(ref cdn) => assert_eq!(cdn.http_redirect_code, Some(301)),
+            ref other => panic!("unexpected redirect {:?}", other),
+        }
+        match ret.routing_rules[2].redirect {
+            Redirect::External(ref external) => assert_eq!(
+                external.rewrite.replace_key_with.as_deref(),
+                Some("prefix/${key}")
+            ),
+            ref other => panic!("unexpected redirect {:?}", other),
+        }
+
+        let parsed: WebsiteConfiguration = quick_xml::de::from_str(&ret.to_xml()).unwrap();
+        assert_eq!(parsed, ret);
+    }
+}
@@ -0,0 +1,37 @@
+/// Escape `text` so that it can be embedded in an XML document.
+pub(crate) fn escape(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&apos;"),
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+/// Implement `Deserialize` through `FromStr`, for enums sent as the text of an element.
+macro_rules! deserialize_from_str {
+    ($($ty:ty),*) => {$(
+        impl<'de> serde::Deserialize<'de> for $ty {
+            fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                <String as serde::Deserialize>::deserialize(deserializer)?
+                    .parse()
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+    )*};
+}
+pub(crate) use deserialize_from_str;
+
+#[test]
+fn test_escape() {
+    assert_eq!(escape("\"a\"&<b>'"), "&quot;a&quot;&amp;&lt;b&gt;&apos;");
+}